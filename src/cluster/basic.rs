@@ -1,7 +1,144 @@
-use crate::cluster::ClusterTrait;
-use crate::responses;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Default)]
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PowerSourceKind {
+    Unknown,
+    SingleMains,
+    ThreePhaseMains,
+    Battery,
+    DcSource,
+    EmergencyMainsConstant,
+    EmergencyMainsTransfer,
+    Other(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerSource {
+    pub kind: PowerSourceKind,
+    pub secondary_battery: bool,
+}
+
+impl PowerSource {
+    pub fn from_u8(power_source: u8) -> Self {
+        let kind = match power_source & 0x7f {
+            0x00 => PowerSourceKind::Unknown,
+            0x01 => PowerSourceKind::SingleMains,
+            0x02 => PowerSourceKind::ThreePhaseMains,
+            0x03 => PowerSourceKind::Battery,
+            0x04 => PowerSourceKind::DcSource,
+            0x05 => PowerSourceKind::EmergencyMainsConstant,
+            0x06 => PowerSourceKind::EmergencyMainsTransfer,
+            n => PowerSourceKind::Other(n),
+        };
+        Self {
+            kind,
+            secondary_battery: (power_source & 0x80) != 0,
+        }
+    }
+
+    /// Returns true if the device is not running on battery, it is then always awake.
+    pub fn is_mains(&self) -> bool {
+        match self.kind {
+            PowerSourceKind::SingleMains
+            | PowerSourceKind::ThreePhaseMains
+            | PowerSourceKind::DcSource
+            | PowerSourceKind::EmergencyMainsConstant
+            | PowerSourceKind::EmergencyMainsTransfer => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PhysicalEnvironment {
+    Unspecified,
+    Atrium,
+    Bathroom,
+    Bedroom,
+    Office,
+    DiningRoom,
+    Entry,
+    FamilyRoom,
+    Basement,
+    Garage,
+    Hallway,
+    Kitchen,
+    LaundryRoom,
+    Nursery,
+    Outside,
+    Attic,
+    LivingRoom,
+    Patio,
+    Garden,
+    Balcony,
+    Unknown,
+    Other(u8),
+}
+
+impl PhysicalEnvironment {
+    pub fn from_u8(environment: u8) -> Self {
+        match environment {
+            0x00 => Self::Unspecified,
+            0x01 => Self::Atrium,
+            0x04 => Self::Bathroom,
+            0x05 => Self::Bedroom,
+            0x0b => Self::Office,
+            0x0e => Self::DiningRoom,
+            0x11 => Self::Entry,
+            0x12 => Self::FamilyRoom,
+            0x16 => Self::Basement,
+            0x19 => Self::Garage,
+            0x1b => Self::Hallway,
+            0x1d => Self::Kitchen,
+            0x1e => Self::LaundryRoom,
+            0x22 => Self::Nursery,
+            0x25 => Self::Outside,
+            0x2c => Self::Attic,
+            0x2e => Self::LivingRoom,
+            0x36 => Self::Patio,
+            0x44 => Self::Garden,
+            0x45 => Self::Balcony,
+            0xff => Self::Unknown,
+            n => Self::Other(n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct AlarmMask {
+    pub hardware_fault: bool,
+    pub software_fault: bool,
+}
+
+impl AlarmMask {
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            hardware_fault: (bits & 0x1) != 0,
+            software_fault: (bits & 0x2) != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DisableLocalConfig {
+    pub reset_disabled: bool,
+    pub device_config_disabled: bool,
+}
+
+impl DisableLocalConfig {
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            reset_disabled: (bits & 0x1) != 0,
+            device_config_disabled: (bits & 0x2) != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct C0000 {
     pub zcl_version: u8,
     pub application_version: u8,
@@ -10,35 +147,36 @@ pub struct C0000 {
     pub manufacturer_name: String,
     pub model_identifier: String,
     pub date_code: String,
-    pub power_source: u8,
+    pub power_source: Option<PowerSource>,
     pub location_description: String,
-    pub physical_environment: u8,
+    pub physical_environment: Option<PhysicalEnvironment>,
     pub device_enabled: bool,
-    pub alarm_mask: u8,
-    pub disable_local_config: u8,
+    pub alarm_mask: Option<AlarmMask>,
+    pub disable_local_config: Option<DisableLocalConfig>,
     pub sw_build_id: String,
+    pub attributes: BTreeMap<u16, Attribute>,
 }
 
 impl ClusterTrait for C0000 {
     fn new() -> Self {
         Self::default()
     }
-    fn update(&mut self, msg: &responses::ReadAttributeResponse) {
-        match msg.attr_enum {
-            0x0 => self.zcl_version = msg.data_as_u8().unwrap_or(0),
-            0x1 => self.application_version = msg.data_as_u8().unwrap_or(0),
-            0x2 => self.stack_version = msg.data_as_u8().unwrap_or(0),
-            0x3 => self.hw_version = msg.data_as_u8().unwrap_or(0),
-            0x4 => self.manufacturer_name = msg.data_as_str().unwrap_or("").into(),
-            0x5 => self.model_identifier = msg.data_as_str().unwrap_or("").into(),
-            0x6 => self.date_code = msg.data_as_str().unwrap_or("").into(),
-            0x7 => self.power_source = msg.data_as_u8().unwrap_or(0), // FIXME make enum
-            0x10 => self.location_description = msg.data_as_str().unwrap_or("").into(),
-            0x11 => self.physical_environment = msg.data_as_u8().unwrap_or(0), // FIXME make enum
-            0x12 => self.device_enabled = msg.data_as_bool().unwrap_or(true),
-            0x13 => self.alarm_mask = msg.data_as_u8().unwrap_or(0), // FIXME make enum ?
-            0x14 => self.disable_local_config = msg.data_as_u8().unwrap_or(0), // FIXME make enum ?
-            0x16 => self.sw_build_id = msg.data_as_str().unwrap_or("").into(),
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0 => self.zcl_version = value.as_u8().unwrap_or(0),
+            0x1 => self.application_version = value.as_u8().unwrap_or(0),
+            0x2 => self.stack_version = value.as_u8().unwrap_or(0),
+            0x3 => self.hw_version = value.as_u8().unwrap_or(0),
+            0x4 => self.manufacturer_name = value.as_str().unwrap_or("").into(),
+            0x5 => self.model_identifier = value.as_str().unwrap_or("").into(),
+            0x6 => self.date_code = value.as_str().unwrap_or("").into(),
+            0x7 => self.power_source = value.as_u8().map(PowerSource::from_u8),
+            0x10 => self.location_description = value.as_str().unwrap_or("").into(),
+            0x11 => self.physical_environment = value.as_u8().map(PhysicalEnvironment::from_u8),
+            0x12 => self.device_enabled = value.as_bool().unwrap_or(true),
+            0x13 => self.alarm_mask = value.as_u8().map(AlarmMask::from_bits),
+            0x14 => self.disable_local_config = value.as_u8().map(DisableLocalConfig::from_bits),
+            0x4000 => self.sw_build_id = value.as_str().unwrap_or("").into(),
             _ => {}
         }
     }
@@ -0,0 +1,136 @@
+use bytebuffer::ByteBuffer;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LockState {
+    NotFullyLocked,
+    Locked,
+    Unlocked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LockType {
+    DeadBolt,
+    Magnetic,
+    Other,
+    Mortise,
+    Rim,
+    LatchBolt,
+    CylindricalLock,
+    TubularLock,
+    InterconnectedLock,
+    DeadLatch,
+    DoorFurniture,
+    Unknown(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DoorState {
+    Open,
+    Closed,
+    Jammed,
+    ForcedOpen,
+    Error,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0101 {
+    pub lock_state: Option<LockState>,
+    pub lock_type: Option<LockType>,
+    pub actuator_enabled: Option<bool>,
+    pub door_state: Option<DoorState>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl ClusterTrait for C0101 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => {
+                self.lock_state = match value.as_u8() {
+                    Some(0) => Some(LockState::NotFullyLocked),
+                    Some(1) => Some(LockState::Locked),
+                    Some(2) => Some(LockState::Unlocked),
+                    _ => None,
+                }
+            }
+            0x0001 => {
+                self.lock_type = match value.as_u8() {
+                    Some(0) => Some(LockType::DeadBolt),
+                    Some(1) => Some(LockType::Magnetic),
+                    Some(2) => Some(LockType::Other),
+                    Some(3) => Some(LockType::Mortise),
+                    Some(4) => Some(LockType::Rim),
+                    Some(5) => Some(LockType::LatchBolt),
+                    Some(6) => Some(LockType::CylindricalLock),
+                    Some(7) => Some(LockType::TubularLock),
+                    Some(8) => Some(LockType::InterconnectedLock),
+                    Some(9) => Some(LockType::DeadLatch),
+                    Some(10) => Some(LockType::DoorFurniture),
+                    Some(n) => Some(LockType::Unknown(n)),
+                    None => None,
+                }
+            }
+            0x0002 => self.actuator_enabled = value.as_bool(),
+            0x0003 => {
+                self.door_state = match value.as_u8() {
+                    Some(0) => Some(DoorState::Open),
+                    Some(1) => Some(DoorState::Closed),
+                    Some(2) => Some(DoorState::Jammed),
+                    Some(3) => Some(DoorState::ForcedOpen),
+                    Some(4) => Some(DoorState::Error),
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Operation Event Notification (command 0x20) sent by a door lock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationEvent {
+    pub source: u8,     // 0 keypad, 1 RF, 2 manual, 3 RFID, 0xff indeterminate
+    pub event_code: u8, // 1 lock, 2 unlock, 8 key lock, 9 key unlock, 10 auto lock, ...
+    pub user_id: u16,
+    pub pin: Vec<u8>,
+    pub local_time: u32,
+}
+
+impl OperationEvent {
+    /// Decodes the command payload, which is sent over the air in little endian.
+    pub fn from_zcl_payload(payload: &[u8]) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(payload);
+        if payload.len() < 9 {
+            return Err("Not enough data");
+        }
+        let source = buf.read_u8().unwrap();
+        let event_code = buf.read_u8().unwrap();
+        let user_id = buf.read_u16().unwrap().swap_bytes();
+        let pin_len = buf.read_u8().unwrap() as usize;
+        let pin = match pin_len {
+            0xff => vec![],
+            _ => match buf.read_bytes(pin_len) {
+                Ok(pin) => pin,
+                Err(_) => return Err("Failed to read PIN"),
+            },
+        };
+        let local_time = match buf.read_u32() {
+            Ok(local_time) => local_time.swap_bytes(),
+            Err(_) => return Err("Failed to read local time"),
+        };
+        Ok(Self {
+            source,
+            event_code,
+            user_id,
+            pin,
+            local_time,
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+/// Electrical measurement, raw values are kept as reported and scaled with the
+/// multiplier and divisor attributes by the accessors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0B04 {
+    pub measurement_type: Option<u32>,
+    pub rms_voltage: Option<u16>,
+    pub rms_current: Option<u16>,
+    pub active_power: Option<i16>,
+    pub ac_voltage_multiplier: Option<u16>,
+    pub ac_voltage_divisor: Option<u16>,
+    pub ac_current_multiplier: Option<u16>,
+    pub ac_current_divisor: Option<u16>,
+    pub ac_power_multiplier: Option<u16>,
+    pub ac_power_divisor: Option<u16>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn scale(value: f32, multiplier: Option<u16>, divisor: Option<u16>) -> f32 {
+    let multiplier = multiplier.unwrap_or(1).max(1) as f32;
+    let divisor = divisor.unwrap_or(1).max(1) as f32;
+    value * multiplier / divisor
+}
+
+impl C0B04 {
+    /// RMS voltage in V.
+    pub fn voltage(&self) -> Option<f32> {
+        self.rms_voltage.map(|v| {
+            scale(
+                v as f32,
+                self.ac_voltage_multiplier,
+                self.ac_voltage_divisor,
+            )
+        })
+    }
+
+    /// RMS current in A.
+    pub fn current(&self) -> Option<f32> {
+        self.rms_current.map(|v| {
+            scale(
+                v as f32,
+                self.ac_current_multiplier,
+                self.ac_current_divisor,
+            )
+        })
+    }
+
+    /// Active power in W.
+    pub fn power(&self) -> Option<f32> {
+        self.active_power
+            .map(|v| scale(v as f32, self.ac_power_multiplier, self.ac_power_divisor))
+    }
+}
+
+impl ClusterTrait for C0B04 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.measurement_type = value.as_u32(),
+            0x0505 => self.rms_voltage = value.as_u16().filter(|v| *v != 0xffff),
+            0x0508 => self.rms_current = value.as_u16().filter(|v| *v != 0xffff),
+            0x050b => self.active_power = value.as_i16().filter(|v| *v != -0x8000),
+            0x0600 => self.ac_voltage_multiplier = value.as_u16(),
+            0x0601 => self.ac_voltage_divisor = value.as_u16(),
+            0x0602 => self.ac_current_multiplier = value.as_u16(),
+            0x0603 => self.ac_current_divisor = value.as_u16(),
+            0x0604 => self.ac_power_multiplier = value.as_u16(),
+            0x0605 => self.ac_power_divisor = value.as_u16(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ZoneType {
+    StandardCie,
+    MotionSensor,
+    ContactSwitch,
+    FireSensor,
+    WaterSensor,
+    CarbonMonoxideSensor,
+    PersonalEmergencyDevice,
+    VibrationMovementSensor,
+    RemoteControl,
+    KeyFob,
+    Keypad,
+    StandardWarningDevice,
+    GlassBreakSensor,
+    SecurityRepeater,
+    Other(u16),
+}
+
+impl ZoneType {
+    pub fn from_u16(zone_type: u16) -> Self {
+        match zone_type {
+            0x0000 => Self::StandardCie,
+            0x000d => Self::MotionSensor,
+            0x0015 => Self::ContactSwitch,
+            0x0028 => Self::FireSensor,
+            0x002a => Self::WaterSensor,
+            0x002b => Self::CarbonMonoxideSensor,
+            0x002c => Self::PersonalEmergencyDevice,
+            0x002d => Self::VibrationMovementSensor,
+            0x010f => Self::RemoteControl,
+            0x0115 => Self::KeyFob,
+            0x021d => Self::Keypad,
+            0x0225 => Self::StandardWarningDevice,
+            0x0226 => Self::GlassBreakSensor,
+            0x0229 => Self::SecurityRepeater,
+            n => Self::Other(n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ZoneStatus {
+    pub alarm1: bool,
+    pub alarm2: bool,
+    pub tamper: bool,
+    pub battery: bool,
+    pub supervision_reports: bool,
+    pub restore_reports: bool,
+    pub trouble: bool,
+    pub ac_mains: bool,
+    pub test: bool,
+    pub battery_defect: bool,
+}
+
+impl ZoneStatus {
+    pub fn from_bits(bits: u16) -> Self {
+        Self {
+            alarm1: (bits & 0x1) != 0,
+            alarm2: (bits & 0x2) != 0,
+            tamper: (bits & 0x4) != 0,
+            battery: (bits & 0x8) != 0,
+            supervision_reports: (bits & 0x10) != 0,
+            restore_reports: (bits & 0x20) != 0,
+            trouble: (bits & 0x40) != 0,
+            ac_mains: (bits & 0x80) != 0,
+            test: (bits & 0x100) != 0,
+            battery_defect: (bits & 0x200) != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0500 {
+    pub enrolled: Option<bool>,
+    pub zone_type: Option<ZoneType>,
+    pub zone_status: Option<ZoneStatus>,
+    pub ias_cie_address: Option<u64>,
+    pub zone_id: Option<u8>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl ClusterTrait for C0500 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.enrolled = value.as_u8().map(|v| v == 1),
+            0x0001 => self.zone_type = value.as_u16().map(ZoneType::from_u16),
+            0x0002 => self.zone_status = value.as_u16().map(ZoneStatus::from_bits),
+            0x0010 => self.ias_cie_address = value.as_u64(),
+            0x0011 => self.zone_id = value.as_u8(),
+            _ => {}
+        }
+    }
+}
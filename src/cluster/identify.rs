@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0003 {
+    pub identify_time: Option<u16>, // seconds
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl ClusterTrait for C0003 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.identify_time = value.as_u16(),
+            _ => {}
+        }
+    }
+}
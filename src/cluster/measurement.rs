@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+/// Illuminance measurement, values are in lux.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0400 {
+    pub measured_value: Option<f32>,
+    pub min_measured_value: Option<f32>,
+    pub max_measured_value: Option<f32>,
+    pub tolerance: Option<u16>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_lux(value: &ZclValue) -> Option<f32> {
+    match value.as_u16() {
+        Some(0) | Some(0xffff) | None => None,
+        Some(v) => Some(10f32.powf((v as f32 - 1.0) / 10000.0)),
+    }
+}
+
+impl ClusterTrait for C0400 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.measured_value = to_lux(value),
+            0x0001 => self.min_measured_value = to_lux(value),
+            0x0002 => self.max_measured_value = to_lux(value),
+            0x0003 => self.tolerance = value.as_u16(),
+            _ => {}
+        }
+    }
+}
+
+/// Temperature measurement, values are in °C.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0402 {
+    pub measured_value: Option<f32>,
+    pub min_measured_value: Option<f32>,
+    pub max_measured_value: Option<f32>,
+    pub tolerance: Option<f32>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_celsius(value: &ZclValue) -> Option<f32> {
+    match value.as_i16() {
+        Some(-0x8000) | None => None,
+        Some(v) => Some(v as f32 / 100.0),
+    }
+}
+
+impl ClusterTrait for C0402 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.measured_value = to_celsius(value),
+            0x0001 => self.min_measured_value = to_celsius(value),
+            0x0002 => self.max_measured_value = to_celsius(value),
+            0x0003 => self.tolerance = value.as_u16().map(|v| v as f32 / 100.0),
+            _ => {}
+        }
+    }
+}
+
+/// Pressure measurement, values are in hPa. The scaled value is kept as reported and converted
+/// with the scale by `scaled_pressure()`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0403 {
+    pub measured_value: Option<f32>,
+    pub min_measured_value: Option<f32>,
+    pub max_measured_value: Option<f32>,
+    pub tolerance: Option<f32>,
+    pub scaled_value: Option<i16>,
+    pub scale: Option<i8>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_hpa(value: &ZclValue) -> Option<f32> {
+    // the measured value is in units of 0.1 kPa, which is 1 hPa
+    match value.as_i16() {
+        Some(-0x8000) | None => None,
+        Some(v) => Some(v as f32),
+    }
+}
+
+impl C0403 {
+    /// The higher precision pressure in hPa, if the device supports it.
+    pub fn scaled_pressure(&self) -> Option<f32> {
+        let scale = self.scale.unwrap_or(0) as i32;
+        self.scaled_value.map(|v| v as f32 * 10f32.powi(-scale - 2))
+    }
+}
+
+impl ClusterTrait for C0403 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.measured_value = to_hpa(value),
+            0x0001 => self.min_measured_value = to_hpa(value),
+            0x0002 => self.max_measured_value = to_hpa(value),
+            0x0003 => self.tolerance = value.as_u16().map(|v| v as f32),
+            0x0010 => {
+                self.scaled_value = match value.as_i16() {
+                    Some(-0x8000) | None => None,
+                    Some(v) => Some(v),
+                }
+            }
+            0x0014 => self.scale = value.as_i64().map(|v| v as i8),
+            _ => {}
+        }
+    }
+}
+
+/// Relative humidity measurement, values are in %RH.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0405 {
+    pub measured_value: Option<f32>,
+    pub min_measured_value: Option<f32>,
+    pub max_measured_value: Option<f32>,
+    pub tolerance: Option<f32>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_relative_humidity(value: &ZclValue) -> Option<f32> {
+    match value.as_u16() {
+        Some(0xffff) | None => None,
+        Some(v) => Some(v as f32 / 100.0),
+    }
+}
+
+impl ClusterTrait for C0405 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.measured_value = to_relative_humidity(value),
+            0x0001 => self.min_measured_value = to_relative_humidity(value),
+            0x0002 => self.max_measured_value = to_relative_humidity(value),
+            0x0003 => self.tolerance = value.as_u16().map(|v| v as f32 / 100.0),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OccupancySensorType {
+    Pir,
+    Ultrasonic,
+    PirAndUltrasonic,
+    PhysicalContact,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0406 {
+    pub occupied: Option<bool>,
+    pub sensor_type: Option<OccupancySensorType>,
+    pub pir_occupied_to_unoccupied_delay: Option<u16>, // seconds
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl ClusterTrait for C0406 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.occupied = value.as_u8().map(|v| v & 0x1 != 0),
+            0x0001 => {
+                self.sensor_type = match value.as_u8() {
+                    Some(0) => Some(OccupancySensorType::Pir),
+                    Some(1) => Some(OccupancySensorType::Ultrasonic),
+                    Some(2) => Some(OccupancySensorType::PirAndUltrasonic),
+                    Some(3) => Some(OccupancySensorType::PhysicalContact),
+                    Some(n) => {
+                        error!("Invalid occupancy sensor type {}", n);
+                        None
+                    }
+                    None => None,
+                }
+            }
+            0x0010 => self.pir_occupied_to_unoccupied_delay = value.as_u16(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+/// Simple metering, raw values are kept as reported and scaled with the
+/// multiplier and divisor attributes by the accessors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0702 {
+    pub current_summation_delivered: Option<u64>,
+    pub unit_of_measure: Option<u8>, // 0 kWh, 1 m³, ...
+    pub multiplier: Option<u32>,
+    pub divisor: Option<u32>,
+    pub summation_formatting: Option<u8>,
+    pub instantaneous_demand: Option<i32>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl C0702 {
+    fn scale(&self, value: f64) -> f64 {
+        let multiplier = self.multiplier.unwrap_or(1).max(1) as f64;
+        let divisor = self.divisor.unwrap_or(1).max(1) as f64;
+        value * multiplier / divisor
+    }
+
+    /// Current summation delivered, in `unit_of_measure` (kWh for electricity).
+    pub fn summation(&self) -> Option<f64> {
+        self.current_summation_delivered
+            .map(|v| self.scale(v as f64))
+    }
+
+    /// Instantaneous demand, in `unit_of_measure` per hour (kW for electricity).
+    pub fn demand(&self) -> Option<f64> {
+        self.instantaneous_demand.map(|v| self.scale(v as f64))
+    }
+
+    /// Current summation delivered, formatted with the number of decimals given by the
+    /// summation formatting attribute.
+    pub fn formatted_summation(&self) -> Option<String> {
+        let decimals = (self.summation_formatting.unwrap_or(0) & 0x7) as usize;
+        self.summation()
+            .map(|summation| format!("{:.*}", decimals, summation))
+    }
+}
+
+impl ClusterTrait for C0702 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.current_summation_delivered = value.as_u64(),
+            0x0300 => self.unit_of_measure = value.as_u8(),
+            0x0301 => self.multiplier = value.as_u32(),
+            0x0302 => self.divisor = value.as_u32(),
+            0x0303 => self.summation_formatting = value.as_u8(),
+            0x0400 => self.instantaneous_demand = value.as_i64().map(|v| v as i32),
+            _ => {}
+        }
+    }
+}
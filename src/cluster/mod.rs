@@ -1,28 +1,65 @@
-use crate::responses;
-use std::stringify;
+use crate::{
+    color::{Gamut, Xy},
+    responses,
+    zcl::ZclValue,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, stringify, time::SystemTime};
 
 mod basic;
-use basic::C0000;
+mod door_lock;
+mod electrical_measurement;
+mod ias_zone;
+mod identify;
+mod measurement;
+mod metering;
+mod power_configuration;
+mod thermostat;
+mod window_covering;
+pub use basic::{
+    AlarmMask, DisableLocalConfig, PhysicalEnvironment, PowerSource, PowerSourceKind, C0000,
+};
+pub use door_lock::{DoorState, LockState, LockType, OperationEvent, C0101};
+pub use electrical_measurement::C0B04;
+pub use ias_zone::{ZoneStatus, ZoneType, C0500};
+pub use identify::C0003;
+pub use measurement::{OccupancySensorType, C0400, C0402, C0403, C0405, C0406};
+pub use metering::C0702;
+pub use power_configuration::C0001;
+pub use thermostat::{RunningState, SystemMode, C0201};
+pub use window_covering::C0102;
 
 macro_rules! make_cluster {
     ( $($cluster_name:ident ($cluster:ident), $id:expr ),+ ) => {
-        #[derive(Debug, Clone)]
+        #[derive(Debug, Clone, Serialize, Deserialize)]
         pub enum Cluster {
             $( $cluster_name($cluster), )+
-            Unk(u16),
+            Unk(UnknownCluster),
         }
 
         impl Cluster {
             pub fn new(id: u16) -> Self {
                 match id {
                     $( $id => Self::$cluster_name($cluster::new()), )+
-                    _ => Self::Unk(id),
+                    _ => Self::Unk(UnknownCluster::new(id)),
                 }
             }
             pub fn id(&self) -> u16 {
                 match self {
                     $( Self::$cluster_name(_) => $id, )+
-                    Self::Unk(id) => *id,
+                    Self::Unk(cluster) => cluster.id,
+                }
+            }
+            pub fn attributes(&self) -> &BTreeMap<u16, Attribute> {
+                match self {
+                    $( Self::$cluster_name(cluster) => &cluster.attributes, )+
+                    Self::Unk(cluster) => &cluster.attributes,
+                }
+            }
+            pub fn attributes_mut(&mut self) -> &mut BTreeMap<u16, Attribute> {
+                match self {
+                    $( Self::$cluster_name(cluster) => &mut cluster.attributes, )+
+                    Self::Unk(cluster) => &mut cluster.attributes,
                 }
             }
             pub fn name(&self) -> Option<String> {
@@ -32,10 +69,36 @@ macro_rules! make_cluster {
                 }
             }
             pub fn update(&mut self, msg: &responses::ReadAttributeResponse) {
+                {
+                    // an error status, e.g. an unsupported attribute, answers a read too
+                    let attr = self.attributes_mut().entry(msg.attr_enum).or_default();
+                    attr.updated = Some(SystemTime::now());
+                    if msg.attr_status != 0 {
+                        attr.value = None;
+                        return;
+                    }
+                }
+                let value = match msg.value() {
+                    Ok(value) => value,
+                    Err(err) => {
+                        warn!("Failed to decode attribute {:#X} of cluster {:#X}: {}",
+                              msg.attr_enum, msg.cluster_id, err);
+                        return;
+                    }
+                };
                 match self {
-                    $( Self::$cluster_name(cluster) => cluster.update(msg), )+
+                    $( Self::$cluster_name(cluster) => cluster.update(msg.attr_enum, &value), )+
                     Self::Unk(_) => {},
                 }
+                let attr = self.attributes_mut().entry(msg.attr_enum).or_default();
+                attr.data_type = msg.attr_data_type;
+                attr.value = Some(value);
+            }
+            pub fn get_attribute(&self, attr_id: u16) -> Option<&Attribute> {
+                self.attributes().get(&attr_id)
+            }
+            pub fn get_value(&self, attr_id: u16) -> Option<&ZclValue> {
+                self.get_attribute(attr_id).and_then(|attr| attr.value.as_ref())
             }
         }
     }
@@ -44,61 +107,137 @@ macro_rules! make_cluster {
 make_cluster!(
     Basic(C0000),
     0x0000,
+    PowerConfiguration(C0001),
+    0x0001,
+    Identify(C0003),
+    0x0003,
     GeneralOnOff(C0006),
     0x0006,
     GeneralLevelControl(C0008),
     0x0008,
+    ClosuresDoorLock(C0101),
+    0x0101,
+    ClosuresWindowCovering(C0102),
+    0x0102,
+    HvacThermostat(C0201),
+    0x0201,
     LightingColorControl(C0300),
-    0x0300
+    0x0300,
+    MeasurementIlluminance(C0400),
+    0x0400,
+    MeasurementTemperature(C0402),
+    0x0402,
+    MeasurementPressure(C0403),
+    0x0403,
+    MeasurementHumidity(C0405),
+    0x0405,
+    MeasurementOccupancy(C0406),
+    0x0406,
+    SecurityIasZone(C0500),
+    0x0500,
+    SmartEnergyMetering(C0702),
+    0x0702,
+    MeasurementElectrical(C0B04),
+    0x0b04
 );
 
 trait ClusterTrait {
     fn new() -> Self;
-    fn update(&mut self, msg: &responses::ReadAttributeResponse);
+    fn update(&mut self, attr_id: u16, value: &ZclValue);
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Attribute {
+    pub data_type: u8,
+    pub access_control: Option<u8>,
+    pub value: Option<ZclValue>,
+    pub updated: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownCluster {
+    pub id: u16,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl UnknownCluster {
+    fn new(id: u16) -> Self {
+        Self {
+            id,
+            attributes: BTreeMap::new(),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct C0006 {
     pub onoff: bool,
+    pub attributes: BTreeMap<u16, Attribute>,
 }
 
 impl ClusterTrait for C0006 {
     fn new() -> Self {
-        Self { onoff: false }
+        Self {
+            onoff: false,
+            attributes: BTreeMap::new(),
+        }
     }
-    fn update(&mut self, msg: &responses::ReadAttributeResponse) {
-        match msg.attr_enum {
-            0 => self.onoff = msg.data_as_bool().unwrap_or(false),
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0 => self.onoff = value.as_bool().unwrap_or(false),
             _ => {}
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct C0008 {
     pub current_level: u8,
+    pub remaining_time: Option<u16>, // tenths of a second
+    pub on_off_transition_time: Option<u16>,
+    pub on_level: Option<u8>,
+    pub on_transition_time: Option<u16>,
+    pub off_transition_time: Option<u16>,
+    pub default_move_rate: Option<u8>,
+    pub attributes: BTreeMap<u16, Attribute>,
 }
 
 impl ClusterTrait for C0008 {
     fn new() -> Self {
-        Self { current_level: 0 }
+        Self {
+            current_level: 0,
+            remaining_time: None,
+            on_off_transition_time: None,
+            on_level: None,
+            on_transition_time: None,
+            off_transition_time: None,
+            default_move_rate: None,
+            attributes: BTreeMap::new(),
+        }
     }
-    fn update(&mut self, msg: &responses::ReadAttributeResponse) {
-        match msg.attr_enum {
-            0 => self.current_level = msg.data_as_u8().unwrap_or(0),
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0 => self.current_level = value.as_u8().unwrap_or(0),
+            0x0001 => self.remaining_time = value.as_u16(),
+            0x0010 => self.on_off_transition_time = value.as_u16(),
+            // 0xff means the on level is undefined
+            0x0011 => self.on_level = value.as_u8().filter(|level| *level != 0xff),
+            0x0012 => self.on_transition_time = value.as_u16().filter(|t| *t != 0xffff),
+            0x0013 => self.off_transition_time = value.as_u16().filter(|t| *t != 0xffff),
+            0x0014 => self.default_move_rate = value.as_u8().filter(|rate| *rate != 0xff),
             _ => {}
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ColorMode {
     HueSat,
     XY,
     Temp,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ColorCapabilities {
     pub hue_sat: bool,
     pub enhanced_hue: bool,
@@ -107,45 +246,56 @@ pub struct ColorCapabilities {
     pub temp: bool,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct C0300 {
     pub current_hue: Option<u8>,
     pub current_saturation: Option<u8>,
+    pub remaining_time: Option<u16>, // tenths of a second
     pub current_x: Option<u16>,
     pub current_y: Option<u16>,
     pub color_temperature: Option<u16>,
     pub color_mode: Option<ColorMode>,
+    pub enhanced_current_hue: Option<u16>,
+    pub color_loop_active: Option<bool>,
+    pub color_loop_direction: Option<u8>, // 0 decrement, 1 increment
+    pub color_loop_time: Option<u16>,     // seconds
+    pub color_loop_start_hue: Option<u16>,
+    pub color_loop_stored_hue: Option<u16>,
     pub color_capabilities: Option<ColorCapabilities>,
     pub color_temp_min: Option<u16>,
     pub color_temp_max: Option<u16>,
+    pub attributes: BTreeMap<u16, Attribute>,
 }
 
 impl ClusterTrait for C0300 {
     fn new() -> Self {
         Self::default()
     }
-    fn update(&mut self, msg: &responses::ReadAttributeResponse) {
-        match msg.attr_enum {
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
             0x0000 => {
-                self.current_hue = msg.data_as_u8().ok();
+                self.current_hue = value.as_u8();
             }
             0x0001 => {
-                self.current_saturation = msg.data_as_u8().ok();
+                self.current_saturation = value.as_u8();
+            }
+            0x0002 => {
+                self.remaining_time = value.as_u16();
             }
             0x0003 => {
-                self.current_x = msg.data_as_u16().ok();
+                self.current_x = value.as_u16();
             }
             0x0004 => {
-                self.current_y = msg.data_as_u16().ok();
+                self.current_y = value.as_u16();
             }
             0x0007 => {
-                self.color_temperature = msg.data_as_u16().ok();
+                self.color_temperature = value.as_u16();
             }
-            0x0008 => match msg.data_as_u8() {
-                Ok(0) => self.color_mode = Some(ColorMode::HueSat),
-                Ok(1) => self.color_mode = Some(ColorMode::XY),
-                Ok(2) => self.color_mode = Some(ColorMode::Temp),
-                Ok(n) => {
+            0x0008 => match value.as_u8() {
+                Some(0) => self.color_mode = Some(ColorMode::HueSat),
+                Some(1) => self.color_mode = Some(ColorMode::XY),
+                Some(2) => self.color_mode = Some(ColorMode::Temp),
+                Some(n) => {
                     error!("Invalid color mode {}", n);
                     self.color_mode = None;
                 }
@@ -153,10 +303,28 @@ impl ClusterTrait for C0300 {
                     self.color_mode = None;
                 }
             },
+            0x4000 => {
+                self.enhanced_current_hue = value.as_u16();
+            }
+            0x4002 => {
+                self.color_loop_active = value.as_bool();
+            }
+            0x4003 => {
+                self.color_loop_direction = value.as_u8();
+            }
+            0x4004 => {
+                self.color_loop_time = value.as_u16();
+            }
+            0x4005 => {
+                self.color_loop_start_hue = value.as_u16();
+            }
+            0x4006 => {
+                self.color_loop_stored_hue = value.as_u16();
+            }
             0x400a => {
-                let caps = match msg.data_as_u16() {
-                    Ok(caps) => caps,
-                    Err(_) => return,
+                let caps = match value.as_u16() {
+                    Some(caps) => caps,
+                    None => return,
                 };
                 let color_caps = ColorCapabilities {
                     hue_sat: (caps & 0x1) != 0,
@@ -168,16 +336,32 @@ impl ClusterTrait for C0300 {
                 self.color_capabilities = Some(color_caps);
             }
             0x400b => {
-                self.color_temp_min = msg.data_as_u16().ok();
+                self.color_temp_min = value.as_u16();
             }
             0x400c => {
-                self.color_temp_max = msg.data_as_u16().ok();
+                self.color_temp_max = value.as_u16();
             }
             _ => {}
         }
     }
 }
 
+impl C0300 {
+    /// Returns the gamut given by the primaries attributes, if they were read.
+    pub fn gamut(&self) -> Option<Gamut> {
+        let primary = |x_id: u16, y_id: u16| {
+            let x = self.attributes.get(&x_id)?.value.as_ref()?.as_u16()?;
+            let y = self.attributes.get(&y_id)?.value.as_ref()?.as_u16()?;
+            Some(Xy::from_zcl(x, y))
+        };
+        Some(Gamut {
+            red: primary(0x0011, 0x0012)?,
+            green: primary(0x0015, 0x0016)?,
+            blue: primary(0x0019, 0x001a)?,
+        })
+    }
+}
+
 //#[derive(Debug, Clone)]
 //pub struct Cluster {
 //    id: u16,
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0001 {
+    pub mains_voltage: Option<f32>,      // V
+    pub battery_voltage: Option<f32>,    // V
+    pub battery_percentage: Option<f32>, // %
+    pub battery_alarm_mask: Option<u8>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+impl ClusterTrait for C0001 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.mains_voltage = value.as_u16().map(|v| v as f32 / 10.0),
+            // 0xff means invalid or unknown
+            0x0020 => {
+                self.battery_voltage = value
+                    .as_u8()
+                    .filter(|v| *v != 0xff)
+                    .map(|v| v as f32 / 10.0)
+            }
+            0x0021 => {
+                self.battery_percentage =
+                    value.as_u8().filter(|v| *v != 0xff).map(|v| v as f32 / 2.0)
+            }
+            0x0035 => self.battery_alarm_mask = value.as_u8(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SystemMode {
+    Off,
+    Auto,
+    Cool,
+    Heat,
+    EmergencyHeating,
+    Precooling,
+    FanOnly,
+    Dry,
+    Sleep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RunningState {
+    pub heat: bool,
+    pub cool: bool,
+    pub fan: bool,
+    pub heat_second_stage: bool,
+    pub cool_second_stage: bool,
+    pub fan_second_stage: bool,
+    pub fan_third_stage: bool,
+}
+
+impl RunningState {
+    pub fn from_bits(bits: u16) -> Self {
+        Self {
+            heat: (bits & 0x1) != 0,
+            cool: (bits & 0x2) != 0,
+            fan: (bits & 0x4) != 0,
+            heat_second_stage: (bits & 0x8) != 0,
+            cool_second_stage: (bits & 0x10) != 0,
+            fan_second_stage: (bits & 0x20) != 0,
+            fan_third_stage: (bits & 0x40) != 0,
+        }
+    }
+}
+
+/// Thermostat, temperatures are in °C and demands in %.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0201 {
+    pub local_temperature: Option<f32>,
+    pub pi_cooling_demand: Option<u8>,
+    pub pi_heating_demand: Option<u8>,
+    pub occupied_cooling_setpoint: Option<f32>,
+    pub occupied_heating_setpoint: Option<f32>,
+    pub system_mode: Option<SystemMode>,
+    pub running_state: Option<RunningState>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_celsius(value: &ZclValue) -> Option<f32> {
+    match value.as_i16() {
+        Some(-0x8000) | None => None,
+        Some(v) => Some(v as f32 / 100.0),
+    }
+}
+
+impl ClusterTrait for C0201 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.local_temperature = to_celsius(value),
+            0x0007 => self.pi_cooling_demand = value.as_u8(),
+            0x0008 => self.pi_heating_demand = value.as_u8(),
+            0x0011 => self.occupied_cooling_setpoint = to_celsius(value),
+            0x0012 => self.occupied_heating_setpoint = to_celsius(value),
+            0x001c => {
+                self.system_mode = match value.as_u8() {
+                    Some(0) => Some(SystemMode::Off),
+                    Some(1) => Some(SystemMode::Auto),
+                    Some(3) => Some(SystemMode::Cool),
+                    Some(4) => Some(SystemMode::Heat),
+                    Some(5) => Some(SystemMode::EmergencyHeating),
+                    Some(6) => Some(SystemMode::Precooling),
+                    Some(7) => Some(SystemMode::FanOnly),
+                    Some(8) => Some(SystemMode::Dry),
+                    Some(9) => Some(SystemMode::Sleep),
+                    Some(n) => {
+                        error!("Invalid system mode {}", n);
+                        None
+                    }
+                    None => None,
+                }
+            }
+            0x0029 => self.running_state = value.as_u16().map(RunningState::from_bits),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cluster::{Attribute, ClusterTrait};
+use crate::zcl::ZclValue;
+
+/// Window covering, percentages are 0 (open) to 100 (closed).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct C0102 {
+    pub covering_type: Option<u8>,
+    pub current_position_lift: Option<u16>,
+    pub current_position_tilt: Option<u16>,
+    pub config_status: Option<u8>,
+    pub current_position_lift_percentage: Option<u8>,
+    pub current_position_tilt_percentage: Option<u8>,
+    pub attributes: BTreeMap<u16, Attribute>,
+}
+
+fn to_percentage(value: &ZclValue) -> Option<u8> {
+    match value.as_u8() {
+        Some(v) if v <= 100 => Some(v),
+        _ => None,
+    }
+}
+
+impl ClusterTrait for C0102 {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn update(&mut self, attr_id: u16, value: &ZclValue) {
+        match attr_id {
+            0x0000 => self.covering_type = value.as_u8(),
+            0x0003 => self.current_position_lift = value.as_u16(),
+            0x0004 => self.current_position_tilt = value.as_u16(),
+            0x0007 => self.config_status = value.as_u8(),
+            0x0008 => self.current_position_lift_percentage = to_percentage(value),
+            0x0009 => self.current_position_tilt_percentage = to_percentage(value),
+            _ => {}
+        }
+    }
+}
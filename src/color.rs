@@ -0,0 +1,401 @@
+//! Conversions between RGB, HSV, CIE xy and color temperature and their ZCL representations.
+
+/// D65 white point, used for black which has no chromaticity.
+const WHITE_POINT: Xy = Xy {
+    x: 0.3127,
+    y: 0.3290,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,        // degrees, 0 to 360
+    pub saturation: f32, // 0 to 1
+    pub value: f32,      // 0 to 1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Xy {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// The triangle of colors a device can reproduce, given by its three primaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamut {
+    pub red: Xy,
+    pub green: Xy,
+    pub blue: Xy,
+}
+
+impl Rgb {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parses `#rrggbb` or `rrggbb`.
+    pub fn from_hex(hex: &str) -> Result<Self, &'static str> {
+        let hex = hex.trim_start_matches('#');
+        if hex.len() != 6 {
+            return Err("RGB hex color must have 6 digits");
+        }
+        let value = u32::from_str_radix(hex, 16).map_err(|_| "Invalid RGB hex color")?;
+        Ok(Self {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        })
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Self {
+        let hue = hsv.hue.rem_euclid(360.0) / 60.0;
+        let saturation = hsv.saturation.clamp(0.0, 1.0);
+        let value = hsv.value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        Self {
+            r: to_byte(r + m),
+            g: to_byte(g + m),
+            b: to_byte(b + m),
+        }
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+        Hsv {
+            hue,
+            saturation,
+            value: max,
+        }
+    }
+
+    /// Converts a color at full brightness from CIE xy, assuming sRGB primaries.
+    pub fn from_xy(xy: Xy) -> Self {
+        if xy.y <= 0.0 {
+            return Self::new(0, 0, 0);
+        }
+        let big_x = xy.x / xy.y;
+        let big_z = (1.0 - xy.x - xy.y) / xy.y;
+        let r = 3.2406 * big_x - 1.5372 - 0.4986 * big_z;
+        let g = -0.9689 * big_x + 1.8758 + 0.0415 * big_z;
+        let b = 0.0557 * big_x - 0.2040 + 1.0570 * big_z;
+        let (r, g, b) = (r.max(0.0), g.max(0.0), b.max(0.0));
+        let max = r.max(g).max(b);
+        if max == 0.0 {
+            return Self::new(0, 0, 0);
+        }
+        Self {
+            r: to_byte(gamma_encode(r / max)),
+            g: to_byte(gamma_encode(g / max)),
+            b: to_byte(gamma_encode(b / max)),
+        }
+    }
+
+    /// Returns the chromaticity of the color, the brightness is lost.
+    pub fn to_xy(&self) -> Xy {
+        let r = gamma_decode(self.r as f32 / 255.0);
+        let g = gamma_decode(self.g as f32 / 255.0);
+        let b = gamma_decode(self.b as f32 / 255.0);
+        let big_x = 0.4124 * r + 0.3576 * g + 0.1805 * b;
+        let big_y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let big_z = 0.0193 * r + 0.1192 * g + 0.9505 * b;
+        let sum = big_x + big_y + big_z;
+        if sum == 0.0 {
+            return WHITE_POINT;
+        }
+        Xy {
+            x: big_x / sum,
+            y: big_y / sum,
+        }
+    }
+
+    pub fn from_kelvin(kelvin: u16) -> Self {
+        Self::from_xy(Xy::from_kelvin(kelvin))
+    }
+}
+
+impl Hsv {
+    /// Converts to the ZCL hue and saturation, both 0 to 254.
+    pub fn to_zcl(&self) -> (u8, u8) {
+        let hue = self.hue.rem_euclid(360.0) / 360.0 * 254.0;
+        let saturation = self.saturation.clamp(0.0, 1.0) * 254.0;
+        (hue.round() as u8, saturation.round() as u8)
+    }
+
+    pub fn from_zcl(hue: u8, saturation: u8) -> Self {
+        Self {
+            hue: hue.min(254) as f32 / 254.0 * 360.0,
+            saturation: saturation.min(254) as f32 / 254.0,
+            value: 1.0,
+        }
+    }
+}
+
+impl Xy {
+    /// Converts to the ZCL CurrentX/CurrentY values, 0xffff is not allowed.
+    pub fn to_zcl(&self) -> (u16, u16) {
+        let to_zcl = |value: f32| (value.max(0.0) * 65536.0).round().min(0xfeff as f32) as u16;
+        (to_zcl(self.x), to_zcl(self.y))
+    }
+
+    pub fn from_zcl(x: u16, y: u16) -> Self {
+        Self {
+            x: x as f32 / 65536.0,
+            y: y as f32 / 65536.0,
+        }
+    }
+
+    /// Approximates the Planckian locus, valid between 1667 K and 25000 K.
+    pub fn from_kelvin(kelvin: u16) -> Self {
+        let t = (kelvin as f32).clamp(1667.0, 25000.0);
+        let x = if t <= 4000.0 {
+            -0.2661239e9 / t.powi(3) - 0.2343589e6 / t.powi(2) + 0.8776956e3 / t + 0.179910
+        } else {
+            -3.0258469e9 / t.powi(3) + 2.107038e6 / t.powi(2) + 0.2226347e3 / t + 0.240390
+        };
+        let y = if t <= 2222.0 {
+            -1.1063814 * x.powi(3) - 1.3481102 * x.powi(2) + 2.1855583 * x - 0.20219683
+        } else if t <= 4000.0 {
+            -0.9549476 * x.powi(3) - 1.3741859 * x.powi(2) + 2.09137 * x - 0.16748867
+        } else {
+            3.081758 * x.powi(3) - 5.873387 * x.powi(2) + 3.7511299 * x - 0.37001483
+        };
+        Self { x, y }
+    }
+}
+
+impl Gamut {
+    pub fn contains(&self, xy: Xy) -> bool {
+        let side = |a: Xy, b: Xy| (b.x - a.x) * (xy.y - a.y) - (b.y - a.y) * (xy.x - a.x);
+        let d1 = side(self.red, self.green);
+        let d2 = side(self.green, self.blue);
+        let d3 = side(self.blue, self.red);
+        let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(has_neg && has_pos)
+    }
+
+    /// Returns the point itself if it is reproducible, otherwise the closest point on the edge of
+    /// the gamut.
+    pub fn clamp(&self, xy: Xy) -> Xy {
+        if self.contains(xy) {
+            return xy;
+        }
+        let edges = [
+            (self.red, self.green),
+            (self.green, self.blue),
+            (self.blue, self.red),
+        ];
+        let mut closest = xy;
+        let mut closest_dist = f32::MAX;
+        for (a, b) in edges.iter() {
+            let point = closest_on_segment(*a, *b, xy);
+            let dist = (point.x - xy.x).powi(2) + (point.y - xy.y).powi(2);
+            if dist < closest_dist {
+                closest = point;
+                closest_dist = dist;
+            }
+        }
+        closest
+    }
+}
+
+/// Converts a color temperature to mireds, clamped to the device limits if known.
+pub fn kelvin_to_mireds(kelvin: u16, min: Option<u16>, max: Option<u16>) -> u16 {
+    let mut mireds = (1_000_000.0 / kelvin.max(1) as f32).round() as u32;
+    if let Some(min) = min.filter(|min| *min != 0) {
+        mireds = mireds.max(min as u32);
+    }
+    if let Some(max) = max.filter(|max| *max != 0) {
+        mireds = mireds.min(max as u32);
+    }
+    // 0xffff is not allowed for ColorTemperatureMireds
+    mireds.clamp(1, 0xfeff) as u16
+}
+
+pub fn mireds_to_kelvin(mireds: u16) -> u16 {
+    (1_000_000.0 / mireds.max(1) as f32).round() as u16
+}
+
+fn closest_on_segment(a: Xy, b: Xy, p: Xy) -> Xy {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len = dx * dx + dy * dy;
+    if len == 0.0 {
+        return a;
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len).clamp(0.0, 1.0);
+    Xy {
+        x: a.x + t * dx,
+        y: a.y + t * dy,
+    }
+}
+
+fn gamma_decode(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn gamma_encode(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        12.92 * value
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32, epsilon: f32) {
+        assert!((a - b).abs() <= epsilon, "{} is not close to {}", a, b);
+    }
+
+    #[test]
+    fn white_is_d65() {
+        let xy = Rgb::new(255, 255, 255).to_xy();
+        assert_close(xy.x, 0.3127, 0.001);
+        assert_close(xy.y, 0.3290, 0.001);
+        assert_eq!(Rgb::new(0, 0, 0).to_xy(), WHITE_POINT);
+    }
+
+    #[test]
+    fn rgb_hex_round_trip() {
+        let rgb = Rgb::from_hex("#ff8000").unwrap();
+        assert_eq!(rgb, Rgb::new(255, 128, 0));
+        assert_eq!(rgb.to_hex(), "#ff8000");
+        assert!(Rgb::from_hex("#fff").is_err());
+    }
+
+    #[test]
+    fn rgb_hsv_round_trip() {
+        for rgb in &[
+            Rgb::new(255, 0, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(0, 0, 255),
+            Rgb::new(255, 128, 0),
+            Rgb::new(12, 34, 56),
+        ] {
+            assert_eq!(Rgb::from_hsv(rgb.to_hsv()), *rgb);
+        }
+    }
+
+    #[test]
+    fn rgb_xy_round_trip() {
+        let xy = Rgb::new(255, 0, 0).to_xy();
+        assert_close(xy.x, 0.64, 0.001);
+        assert_close(xy.y, 0.33, 0.001);
+        for rgb in &[
+            Rgb::new(255, 0, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(0, 0, 255),
+            Rgb::new(255, 255, 255),
+        ] {
+            let back = Rgb::from_xy(rgb.to_xy());
+            assert!(
+                (back.r as i16 - rgb.r as i16).abs() <= 1,
+                "{:?} {:?}",
+                back,
+                rgb
+            );
+            assert!(
+                (back.g as i16 - rgb.g as i16).abs() <= 1,
+                "{:?} {:?}",
+                back,
+                rgb
+            );
+            assert!(
+                (back.b as i16 - rgb.b as i16).abs() <= 1,
+                "{:?} {:?}",
+                back,
+                rgb
+            );
+        }
+    }
+
+    #[test]
+    fn zcl_round_trip() {
+        let (x, y) = WHITE_POINT.to_zcl();
+        let xy = Xy::from_zcl(x, y);
+        assert_close(xy.x, WHITE_POINT.x, 0.0001);
+        assert_close(xy.y, WHITE_POINT.y, 0.0001);
+        let hsv = Hsv::from_zcl(127, 254);
+        assert_eq!(hsv.to_zcl(), (127, 254));
+    }
+
+    #[test]
+    fn kelvin_to_xy() {
+        let xy = Xy::from_kelvin(2700);
+        assert_close(xy.x, 0.459, 0.002);
+        assert_close(xy.y, 0.411, 0.002);
+    }
+
+    #[test]
+    fn gamut_clamp() {
+        let gamut = Gamut {
+            red: Xy { x: 0.692, y: 0.308 },
+            green: Xy { x: 0.17, y: 0.7 },
+            blue: Xy { x: 0.153, y: 0.048 },
+        };
+        assert_eq!(gamut.clamp(WHITE_POINT), WHITE_POINT);
+        // outside, beyond the red to blue edge
+        let xy = Xy { x: 0.5, y: 0.05 };
+        assert!(!gamut.contains(xy));
+        let clamped = gamut.clamp(xy);
+        let (a, b) = (gamut.blue, gamut.red);
+        let cross = (b.x - a.x) * (clamped.y - a.y) - (b.y - a.y) * (clamped.x - a.x);
+        assert_close(cross, 0.0, 0.0001);
+        assert!(clamped.x >= a.x && clamped.x <= b.x);
+    }
+
+    #[test]
+    fn kelvin_mireds() {
+        assert_eq!(kelvin_to_mireds(2700, None, None), 370);
+        assert_eq!(kelvin_to_mireds(10000, Some(153), Some(370)), 153);
+        assert_eq!(kelvin_to_mireds(2000, Some(153), Some(370)), 370);
+        // 0 means the limit is unknown
+        assert_eq!(kelvin_to_mireds(2000, Some(0), Some(0)), 500);
+        assert_eq!(mireds_to_kelvin(370), 2703);
+    }
+}
@@ -15,37 +15,80 @@ pub enum MessageType {
     SetChannelMask = 0x0021,
     SetDeviceType = 0x0023,
     StartNetwork = 0x0024,
+    RemoveDevice = 0x0026,
+    BindRequest = 0x0030,
+    UnbindRequest = 0x0031,
     SimpleDescriptorRequest = 0x0043,
     ActiveEndpoint = 0x0045,
+    ManagementLeaveRequest = 0x0047,
     PermitJoinRequest = 0x0049,
 
+    IdentifySend = 0x0070,
+    IdentifyQuery = 0x0071,
+
     ActionMove = 0x0080,
     ActionMoveOnOff = 0x0081,
     ActionMoveStep = 0x0082,
+    ActionMoveStopOnOff = 0x0084,
     ActionOnOff = 0x0092,
     ActionOnOffTimed = 0x0093,
     ActionOnOffEffect = 0x0094,
 
     ActionMoveToHue = 0x00B0,
+    ActionMoveHue = 0x00B1,
+    ActionStepHue = 0x00B2,
     ActionMoveToSaturation = 0x00B3,
+    ActionMoveSaturation = 0x00B4,
+    ActionStepSaturation = 0x00B5,
     ActionMoveToHueAndSaturation = 0x00B6,
     ActionMoveToColor = 0x00B7,
+    ActionEnhancedMoveToHue = 0x00BA,
+    ActionColorLoopSet = 0x00BF,
 
     ActionMoveToColorTemp = 0x00C0,
+    ActionMoveColorTemp = 0x00C1,
+    ActionStepColorTemp = 0x00C2,
+
+    IdentifyTriggerEffect = 0x00E0,
+
+    ActionLockUnlockDoor = 0x00F0,
+
+    ActionWindowCovering = 0x00FA,
 
     ReadAttributeRequest = 0x0100,
+    WriteAttributeRequest = 0x0110,
+    AttributeDiscoveryRequest = 0x0140,
+    ExtendedAttributeDiscoveryRequest = 0x0141,
+
+    IasZoneEnrollResponse = 0x0400,
+
+    RawApsDataRequest = 0x0530,
 
     // Responses
     Status = 0x8000,
+    DataIndication = 0x8002,
+    NetworkStateResponse = 0x8009,
     VersionList = 0x8010,
+    AckData = 0x8011,
     DevicesList = 0x8015,
+    BindResponse = 0x8030,
+    UnbindResponse = 0x8031,
     SimpleDescriptorResponse = 0x8043,
     ActiveEndpoints = 0x8045,
+    ManagementLeaveResponse = 0x8047,
+    LeaveIndication = 0x8048,
 
     ReadAttributeResponse = 0x8100,
+    DefaultResponse = 0x8101,
     ReportIndividualAttributResponse = 0x8102,
+    WriteAttributeResponse = 0x8110,
+    AttributeDiscoveryResponse = 0x8140,
+    ExtendedAttributeDiscoveryResponse = 0x8141,
+    ZoneEnrollRequest = 0x8400,
+    ZoneStatusChangeNotification = 0x8401,
 
     RouterDiscoveryConfirm = 0x8701,
+    ApsDataConfirmFail = 0x8702,
 
     DeviceAnnounce = 0x004D,
     Unknown,
@@ -98,7 +141,7 @@ impl Command {
         let checksum = buf.read_u8().unwrap();
         trace!("checksum: {}", checksum);
 
-        if msg.len() - 5 != len.into() {
+        if msg.len() - 5 != usize::from(len) {
             trace!("msg.len() {} len {}", msg.len(), len);
             return Err("Wrong data length");
         }
@@ -116,6 +159,11 @@ impl Command {
         Ok(cmd)
     }
 
+    /// The ZiGate appends the link quality of the received frame to every message.
+    pub fn lqi(&self) -> Option<u8> {
+        self.data.last().copied()
+    }
+
     pub fn get_checksum(&self) -> u8 {
         let mut checksum = 0u8;
 
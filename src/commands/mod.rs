@@ -1,6 +1,11 @@
 use byteorder::{BigEndian, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
 
-use crate::command::{Command, MessageType};
+use crate::{
+    command::{Command, MessageType},
+    zcl::ZclValue,
+};
 
 pub fn get_network_state() -> Command {
     Command::new(MessageType::GetNetworkState as u16, vec![]).unwrap()
@@ -43,6 +48,64 @@ pub fn start_network() -> Command {
     Command::new(MessageType::StartNetwork as u16, vec![]).unwrap()
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Destination {
+    Group(u16),
+    Device(u64, u8), // IEEE address, endpoint
+}
+
+fn write_bind_data(
+    data: &mut Vec<u8>,
+    ieee: u64,
+    endpoint: u8,
+    cluster_id: u16,
+    dst: &Destination,
+) {
+    data.write_u64::<BigEndian>(ieee).unwrap();
+    data.push(endpoint);
+    data.write_u16::<BigEndian>(cluster_id).unwrap();
+    match dst {
+        Destination::Group(group) => {
+            data.push(1); // group address mode
+            data.write_u16::<BigEndian>(*group).unwrap();
+        }
+        Destination::Device(dst_ieee, dst_endpoint) => {
+            data.push(3); // IEEE address mode
+            data.write_u64::<BigEndian>(*dst_ieee).unwrap();
+            data.push(*dst_endpoint);
+        }
+    }
+}
+
+/// Asks the parent of a device to remove it, which also works for sleeping end devices.
+pub fn remove_device(parent_ieee: u64, ieee: u64) -> Command {
+    let mut data = vec![];
+    data.write_u64::<BigEndian>(parent_ieee).unwrap();
+    data.write_u64::<BigEndian>(ieee).unwrap();
+    Command::new(MessageType::RemoveDevice as u16, data).unwrap()
+}
+
+pub fn management_leave_request(addr: u16, ieee: u64, rejoin: bool) -> Command {
+    let mut data = vec![];
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.write_u64::<BigEndian>(ieee).unwrap();
+    data.push(rejoin as u8);
+    data.push(0); // don't remove children
+    Command::new(MessageType::ManagementLeaveRequest as u16, data).unwrap()
+}
+
+pub fn bind_request(ieee: u64, endpoint: u8, cluster_id: u16, dst: &Destination) -> Command {
+    let mut data = vec![];
+    write_bind_data(&mut data, ieee, endpoint, cluster_id, dst);
+    Command::new(MessageType::BindRequest as u16, data).unwrap()
+}
+
+pub fn unbind_request(ieee: u64, endpoint: u8, cluster_id: u16, dst: &Destination) -> Command {
+    let mut data = vec![];
+    write_bind_data(&mut data, ieee, endpoint, cluster_id, dst);
+    Command::new(MessageType::UnbindRequest as u16, data).unwrap()
+}
+
 pub fn simple_descriptor_request(addr: u16, endpoint: u8) -> Command {
     let mut data = vec![];
     data.write_u16::<BigEndian>(addr).unwrap();
@@ -64,6 +127,77 @@ pub fn permit_join_request(addr: u16, interval: u8, tc_significance: u8) -> Comm
     Command::new(MessageType::PermitJoinRequest as u16, data).unwrap()
 }
 
+pub fn identify_send(addr: u16, src_endpoint: u8, dst_endpoint: u8, time: u16) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.write_u16::<BigEndian>(time).unwrap();
+    Command::new(MessageType::IdentifySend as u16, data).unwrap()
+}
+
+pub fn identify_query(addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    Command::new(MessageType::IdentifyQuery as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdentifyEffect {
+    Blink = 0x00,
+    Breathe = 0x01,
+    Okay = 0x02,
+    ChannelChange = 0x0b,
+    Finish = 0xfe,
+    Stop = 0xff,
+}
+
+pub fn identify_trigger_effect(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    effect: IdentifyEffect,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(effect as u8);
+    data.push(0); // effect variant
+    Command::new(MessageType::IdentifyTriggerEffect as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OffEffect {
+    FadeToOff,           // fade to off in 0.8 seconds
+    NoFade,              // immediately off
+    DimDownAndFadeToOff, // 50% dim down in 0.8 seconds then fade to off in 12 seconds
+    DyingLight,          // 20% dim up in 0.5 seconds then fade to off in 1 second
+}
+
+impl OffEffect {
+    /// Returns the effect id and effect variant.
+    pub fn id_and_variant(self) -> (u8, u8) {
+        match self {
+            OffEffect::FadeToOff => (0, 0),
+            OffEffect::NoFade => (0, 1),
+            OffEffect::DimDownAndFadeToOff => (0, 2),
+            OffEffect::DyingLight => (1, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    Identify(IdentifyEffect),
+    Off(OffEffect),
+}
+
 pub fn action_move(
     addr: u16,
     src_endpoint: u8,
@@ -83,6 +217,97 @@ pub fn action_move(
     Command::new(MessageType::ActionMove as u16, data).unwrap()
 }
 
+pub fn action_move_step(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    cmd: u8,
+    mode: u8,
+    step_size: u8,
+    transition_time: u16,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(cmd);
+    data.push(mode); // 0 up, 1 down
+    data.push(step_size);
+    data.write_u16::<BigEndian>(transition_time).unwrap();
+    Command::new(MessageType::ActionMoveStep as u16, data).unwrap()
+}
+
+pub fn action_move_stop_onoff(addr: u16, src_endpoint: u8, dst_endpoint: u8, cmd: u8) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(cmd);
+    Command::new(MessageType::ActionMoveStopOnOff as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelDirection {
+    Up = 0,
+    Down = 1,
+}
+
+/// Continuously moves the level until a `LevelStop` is sent.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelMove {
+    pub direction: LevelDirection,
+    pub rate: u8, // units per second
+    pub with_onoff: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LevelStep {
+    pub direction: LevelDirection,
+    pub step_size: u8,
+    pub transition_time: u16, // tenths of a second
+    pub with_onoff: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LevelStop {
+    pub with_onoff: bool,
+}
+
+impl LevelMove {
+    pub fn to_command(&self, addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+        action_move(
+            addr,
+            src_endpoint,
+            dst_endpoint,
+            self.with_onoff as u8,
+            self.direction as u8,
+            self.rate,
+        )
+    }
+}
+
+impl LevelStep {
+    pub fn to_command(&self, addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+        action_move_step(
+            addr,
+            src_endpoint,
+            dst_endpoint,
+            self.with_onoff as u8,
+            self.direction as u8,
+            self.step_size,
+            self.transition_time,
+        )
+    }
+}
+
+impl LevelStop {
+    pub fn to_command(&self, addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+        action_move_stop_onoff(addr, src_endpoint, dst_endpoint, self.with_onoff as u8)
+    }
+}
+
 pub fn action_move_onoff(
     addr: u16,
     src_endpoint: u8,
@@ -241,6 +466,280 @@ pub fn action_move_color_temp(
     Command::new(MessageType::ActionMoveToColorTemp as u16, data).unwrap()
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMoveMode {
+    Stop = 0,
+    Up = 1,
+    Down = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorStepMode {
+    Up = 1,
+    Down = 3,
+}
+
+pub fn action_move_hue(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: ColorMoveMode,
+    rate: u8,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(mode as u8);
+    data.push(rate);
+    Command::new(MessageType::ActionMoveHue as u16, data).unwrap()
+}
+
+pub fn action_step_hue(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: ColorStepMode,
+    step_size: u8,
+    transition_time: u8,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(mode as u8);
+    data.push(step_size);
+    data.push(transition_time);
+    Command::new(MessageType::ActionStepHue as u16, data).unwrap()
+}
+
+pub fn action_move_saturation(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: ColorMoveMode,
+    rate: u8,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(mode as u8);
+    data.push(rate);
+    Command::new(MessageType::ActionMoveSaturation as u16, data).unwrap()
+}
+
+pub fn action_step_saturation(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: ColorStepMode,
+    step_size: u8,
+    transition_time: u8,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(mode as u8);
+    data.push(step_size);
+    data.push(transition_time);
+    Command::new(MessageType::ActionStepSaturation as u16, data).unwrap()
+}
+
+pub fn action_enhanced_move_to_hue(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    enhanced_hue: u16,
+    direction: u8,
+    transition_time: u16,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.write_u16::<BigEndian>(enhanced_hue).unwrap();
+    data.push(direction);
+    data.write_u16::<BigEndian>(transition_time).unwrap();
+    Command::new(MessageType::ActionEnhancedMoveToHue as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorLoopAction {
+    Deactivate = 0,
+    ActivateFromStartHue = 1,
+    ActivateFromCurrentHue = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorLoopDirection {
+    Decrement = 0,
+    Increment = 1,
+}
+
+/// Only the fields that are set are updated on the device.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColorLoopSet {
+    pub action: Option<ColorLoopAction>,
+    pub direction: Option<ColorLoopDirection>,
+    pub time: Option<u16>, // seconds for a full loop
+    pub start_hue: Option<u16>,
+}
+
+pub fn action_color_loop_set(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    color_loop: ColorLoopSet,
+) -> Command {
+    let mut update_flags = 0;
+    if color_loop.action.is_some() {
+        update_flags |= 0x1;
+    }
+    if color_loop.direction.is_some() {
+        update_flags |= 0x2;
+    }
+    if color_loop.time.is_some() {
+        update_flags |= 0x4;
+    }
+    if color_loop.start_hue.is_some() {
+        update_flags |= 0x8;
+    }
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(update_flags);
+    data.push(color_loop.action.map_or(0, |action| action as u8));
+    data.push(color_loop.direction.map_or(0, |direction| direction as u8));
+    data.write_u16::<BigEndian>(color_loop.time.unwrap_or(0))
+        .unwrap();
+    data.write_u16::<BigEndian>(color_loop.start_hue.unwrap_or(0))
+        .unwrap();
+    Command::new(MessageType::ActionColorLoopSet as u16, data).unwrap()
+}
+
+pub fn action_move_color_temp_rate(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: ColorMoveMode,
+    rate: u16,
+    color_temp_min: u16,
+    color_temp_max: u16,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(mode as u8);
+    data.write_u16::<BigEndian>(rate).unwrap();
+    data.write_u16::<BigEndian>(color_temp_min).unwrap();
+    data.write_u16::<BigEndian>(color_temp_max).unwrap();
+    Command::new(MessageType::ActionMoveColorTemp as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColorTempStep {
+    pub mode: ColorStepMode,
+    pub step_size: u16,       // mireds
+    pub transition_time: u16, // tenths of a second
+    pub color_temp_min: u16,  // mireds, 0 for the device limit
+    pub color_temp_max: u16,  // mireds, 0 for the device limit
+}
+
+impl ColorTempStep {
+    pub fn to_command(&self, addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+        let mut data = vec![];
+        data.push(2); // short address mode
+        data.write_u16::<BigEndian>(addr).unwrap();
+        data.push(src_endpoint);
+        data.push(dst_endpoint);
+        data.push(self.mode as u8);
+        data.write_u16::<BigEndian>(self.step_size).unwrap();
+        data.write_u16::<BigEndian>(self.transition_time).unwrap();
+        data.write_u16::<BigEndian>(self.color_temp_min).unwrap();
+        data.write_u16::<BigEndian>(self.color_temp_max).unwrap();
+        Command::new(MessageType::ActionStepColorTemp as u16, data).unwrap()
+    }
+}
+
+/// Stops any move or step of hue, saturation or color temperature. The ZiGate has no
+/// dedicated message for it, so the ZCL command is sent as raw APS data.
+pub fn action_stop_move_step(addr: u16, src_endpoint: u8, dst_endpoint: u8) -> Command {
+    let frame = zcl_frame(0x47, &[]);
+    raw_aps_data_request(addr, src_endpoint, dst_endpoint, 0x0300, 0x0104, &frame)
+}
+
+pub fn action_lock_unlock_door(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    lock: bool,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(!lock as u8); // 0 lock, 1 unlock
+    Command::new(MessageType::ActionLockUnlockDoor as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoverCommand {
+    Open,
+    Close,
+    Stop,
+    GoToLiftValue(u16),
+    GoToLiftPercentage(u8),
+    GoToTiltValue(u16),
+    GoToTiltPercentage(u8),
+}
+
+pub fn action_window_covering(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    cmd: CoverCommand,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    match cmd {
+        CoverCommand::Open => data.push(0),
+        CoverCommand::Close => data.push(1),
+        CoverCommand::Stop => data.push(2),
+        CoverCommand::GoToLiftValue(value) => {
+            data.push(4);
+            data.write_u16::<BigEndian>(value).unwrap();
+        }
+        CoverCommand::GoToLiftPercentage(percentage) => {
+            data.push(5);
+            data.push(percentage);
+        }
+        CoverCommand::GoToTiltValue(value) => {
+            data.push(7);
+            data.write_u16::<BigEndian>(value).unwrap();
+        }
+        CoverCommand::GoToTiltPercentage(percentage) => {
+            data.push(8);
+            data.push(percentage);
+        }
+    }
+    Command::new(MessageType::ActionWindowCovering as u16, data).unwrap()
+}
+
 pub fn read_attribute_request(
     addr: u16,
     src_endpoint: u8,
@@ -271,6 +770,48 @@ pub fn read_attribute_request(
     Command::new(MessageType::ReadAttributeRequest as u16, data).unwrap()
 }
 
+pub fn write_attribute_request(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    cluster_id: u16,
+    direction: u8,
+    manuf_id: u16,
+    attr_list: Vec<(u16, ZclValue)>,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.write_u16::<BigEndian>(cluster_id).unwrap();
+    data.push(direction); // direction (0 = client to server)
+    let manuf_spec = match manuf_id {
+        0 => 0,
+        _ => 1,
+    };
+    data.push(manuf_spec);
+    data.write_u16::<BigEndian>(manuf_id).unwrap(); // manufacturer code
+    let attr_len = attr_list.len() as u8;
+    data.push(attr_len);
+    for (attr, value) in attr_list {
+        data.write_u16::<BigEndian>(attr).unwrap();
+        data.push(value.data_type());
+        data.extend(value.to_bytes());
+    }
+    Command::new(MessageType::WriteAttributeRequest as u16, data).unwrap()
+}
+
+pub fn simple_write_attribute_request(
+    addr: u16,
+    endpoint: u8,
+    cluster_id: u16,
+    attr: u16,
+    value: ZclValue,
+) -> Command {
+    write_attribute_request(addr, 1, endpoint, cluster_id, 0, 0, vec![(attr, value)])
+}
+
 pub fn simple_read_attribute_request(
     addr: u16,
     endpoint: u8,
@@ -288,3 +829,127 @@ pub fn simple_read_attribute_request_vec(
 ) -> Command {
     read_attribute_request(addr, 1, endpoint, cluster_id, 0, 0, attr)
 }
+
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeDiscovery {
+    pub cluster_id: u16,
+    pub start_attr: u16,
+    pub direction: u8, // 0 = server to client
+    pub manuf_id: u16, // 0 if not manufacturer specific
+    pub max_attrs: u8,
+}
+
+fn write_attribute_discovery_data(
+    data: &mut Vec<u8>,
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    discovery: &AttributeDiscovery,
+) {
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.write_u16::<BigEndian>(discovery.cluster_id).unwrap();
+    data.write_u16::<BigEndian>(discovery.start_attr).unwrap();
+    data.push(discovery.direction); // direction (0 = server to client)
+    let manuf_spec = match discovery.manuf_id {
+        0 => 0,
+        _ => 1,
+    };
+    data.push(manuf_spec);
+    data.write_u16::<BigEndian>(discovery.manuf_id).unwrap(); // manufacturer code
+    data.push(discovery.max_attrs);
+}
+
+pub fn attribute_discovery_request(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    discovery: &AttributeDiscovery,
+) -> Command {
+    let mut data = vec![];
+    write_attribute_discovery_data(&mut data, addr, src_endpoint, dst_endpoint, discovery);
+    Command::new(MessageType::AttributeDiscoveryRequest as u16, data).unwrap()
+}
+
+pub fn extended_attribute_discovery_request(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    discovery: &AttributeDiscovery,
+) -> Command {
+    let mut data = vec![];
+    write_attribute_discovery_data(&mut data, addr, src_endpoint, dst_endpoint, discovery);
+    Command::new(MessageType::ExtendedAttributeDiscoveryRequest as u16, data).unwrap()
+}
+
+pub fn ias_zone_enroll_response(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    enroll_code: u8,
+    zone_id: u8,
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.push(enroll_code); // 0 = success
+    data.push(zone_id);
+    Command::new(MessageType::IasZoneEnrollResponse as u16, data).unwrap()
+}
+
+static ZCL_SEQ_NUM: AtomicU8 = AtomicU8::new(0);
+
+/// Builds a cluster specific, client to server ZCL frame.
+fn zcl_frame(command_id: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![];
+    frame.push(0x01); // frame control: cluster specific
+    frame.push(ZCL_SEQ_NUM.fetch_add(1, Ordering::Relaxed));
+    frame.push(command_id);
+    frame.extend(payload);
+    frame
+}
+
+pub fn raw_aps_data_request(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    cluster_id: u16,
+    profile_id: u16,
+    payload: &[u8],
+) -> Command {
+    let mut data = vec![];
+    data.push(2); // short address mode
+    data.write_u16::<BigEndian>(addr).unwrap();
+    data.push(src_endpoint);
+    data.push(dst_endpoint);
+    data.write_u16::<BigEndian>(cluster_id).unwrap();
+    data.write_u16::<BigEndian>(profile_id).unwrap();
+    data.push(0x02); // security mode: network key
+    data.push(0); // radius: default
+    data.push(payload.len() as u8);
+    data.extend(payload);
+    Command::new(MessageType::RawApsDataRequest as u16, data).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetpointMode {
+    Heat = 0,
+    Cool = 1,
+    Both = 2,
+}
+
+/// `amount` is in steps of 0.1 °C.
+pub fn thermostat_setpoint_raise_lower(
+    addr: u16,
+    src_endpoint: u8,
+    dst_endpoint: u8,
+    mode: SetpointMode,
+    amount: i8,
+) -> Command {
+    let frame = zcl_frame(0x00, &[mode as u8, amount as u8]);
+    raw_aps_data_request(addr, src_endpoint, dst_endpoint, 0x0201, 0x0104, &frame)
+}
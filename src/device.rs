@@ -1,13 +1,70 @@
-use crate::{cluster::Cluster, responses};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
 
-#[derive(Debug, Clone)]
+use crate::{
+    cluster::{Attribute, Cluster},
+    commands::Destination,
+    responses,
+    zcl::ZclValue,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Device {
     pub id: Option<u8>,
     pub short_address: u16,
     pub ieee_address: u64,
     pub power_source: Option<bool>,
     pub link_quality: Option<u8>,
+    #[serde(default)]
+    pub last_seen: Option<SystemTime>,
+    #[serde(default = "default_available")]
+    pub available: bool,
+    #[serde(skip)]
+    pub ping_sent: Option<SystemTime>,
     pub endpoints: Vec<Endpoint>,
+    pub bindings: Vec<Binding>,
+    pub interview_state: InterviewState,
+    #[serde(skip)]
+    pub interview_attempts: u8,
+    #[serde(skip)]
+    pub interview_deadline: Option<SystemTime>,
+    #[serde(skip)]
+    pub pending_basic_attributes: Vec<u16>,
+}
+
+fn default_available() -> bool {
+    true
+}
+
+/// Each state is the last interview step that succeeded, the next one is in progress.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InterviewState {
+    Announced,
+    EndpointsKnown,
+    DescriptorsKnown,
+    BasicRead,
+    Complete,
+    Failed,
+}
+
+impl InterviewState {
+    pub fn in_progress(&self) -> bool {
+        match self {
+            Self::Complete | Self::Failed => false,
+            _ => true,
+        }
+    }
+}
+
+/// Basic attributes read at the end of the interview: manufacturer name, model identifier,
+/// power source and software build id.
+pub const INTERVIEW_BASIC_ATTRIBUTES: [u16; 4] = [0x0004, 0x0005, 0x0007, 0x4000];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Binding {
+    pub endpoint: u8,
+    pub cluster_id: u16,
+    pub destination: Destination,
 }
 
 impl Device {
@@ -18,7 +75,15 @@ impl Device {
             id: None,
             power_source: None,
             link_quality: None,
+            last_seen: None,
+            available: true,
+            ping_sent: None,
             endpoints: Vec::new(),
+            bindings: Vec::new(),
+            interview_state: InterviewState::Announced,
+            interview_attempts: 0,
+            interview_deadline: None,
+            pending_basic_attributes: Vec::new(),
         }
     }
 
@@ -29,7 +94,15 @@ impl Device {
             id: Some(device.id),
             power_source: Some(device.power_source),
             link_quality: Some(device.link_quality),
+            last_seen: None,
+            available: true,
+            ping_sent: None,
             endpoints: Vec::new(),
+            bindings: Vec::new(),
+            interview_state: InterviewState::Announced,
+            interview_attempts: 0,
+            interview_deadline: None,
+            pending_basic_attributes: Vec::new(),
         }
     }
 
@@ -38,15 +111,25 @@ impl Device {
             id: None,
             short_address: msg.short_address,
             ieee_address: msg.ieee_address,
-            power_source: None,
+            power_source: Some((msg.mac_capability & 0x04) != 0), // mains powered
             link_quality: None,
+            last_seen: None,
+            available: true,
+            ping_sent: None,
             endpoints: Vec::new(),
+            bindings: Vec::new(),
+            interview_state: InterviewState::Announced,
+            interview_attempts: 0,
+            interview_deadline: None,
+            pending_basic_attributes: Vec::new(),
         }
     }
 
     pub fn add_endpoints(&mut self, endpoints: &Vec<u8>) {
         for endpoint in endpoints {
-            self.endpoints.push(Endpoint::new(*endpoint));
+            if self.get_endpoint(*endpoint).is_none() {
+                self.endpoints.push(Endpoint::new(*endpoint));
+            }
         }
     }
 
@@ -65,9 +148,25 @@ impl Device {
             }
             endpoint.set_in_clusters(in_clusters);
             endpoint.set_out_clusters(out_clusters);
+            endpoint.profile = Some(msg.profile);
+            endpoint.device_id = Some(msg.device_id);
+            endpoint.version = Some(msg.version);
+            endpoint.descriptor_known = true;
         }
     }
 
+    pub fn descriptors_known(&self) -> bool {
+        !self.endpoints.is_empty() && self.endpoints.iter().all(|ep| ep.descriptor_known)
+    }
+
+    /// Returns the first endpoint implementing the given input cluster.
+    pub fn find_endpoint_with_cluster(&self, cluster_id: u16) -> Option<u8> {
+        self.endpoints
+            .iter()
+            .find(|endpoint| endpoint.get_in_cluster(cluster_id).is_some())
+            .map(|endpoint| endpoint.id)
+    }
+
     pub fn get_endpoint(&self, id: u8) -> Option<&Endpoint> {
         for endpoint in &self.endpoints {
             if endpoint.id == id {
@@ -77,7 +176,7 @@ impl Device {
         None
     }
 
-    fn get_mut_endpoint(&mut self, id: u8) -> Option<&mut Endpoint> {
+    pub fn get_mut_endpoint(&mut self, id: u8) -> Option<&mut Endpoint> {
         for endpoint in &mut self.endpoints {
             if endpoint.id == id {
                 return Some(endpoint);
@@ -91,13 +190,193 @@ impl Device {
             endpoint.update_cluster(&msg);
         }
     }
+
+    pub fn set_attribute_info(
+        &mut self,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_id: u16,
+        data_type: u8,
+        access_control: Option<u8>,
+    ) {
+        if let Some(endpoint) = self.get_mut_endpoint(endpoint) {
+            endpoint.set_attribute_info(cluster_id, attr_id, data_type, access_control);
+        }
+    }
+
+    pub fn get_attribute(&self, endpoint: u8, cluster_id: u16, attr_id: u16) -> Option<&Attribute> {
+        self.get_endpoint(endpoint)
+            .and_then(|endpoint| endpoint.get_attribute(cluster_id, attr_id))
+    }
+
+    pub fn get_value(&self, endpoint: u8, cluster_id: u16, attr_id: u16) -> Option<&ZclValue> {
+        self.get_endpoint(endpoint)
+            .and_then(|endpoint| endpoint.get_value(cluster_id, attr_id))
+    }
+
+    /// Returns true if the device is known to be mains powered, from its Basic cluster or from
+    /// what the ZiGate reported.
+    pub fn is_mains(&self) -> bool {
+        for endpoint in &self.endpoints {
+            if let Some(Cluster::Basic(cluster)) = endpoint.get_in_cluster(0x0000) {
+                if let Some(power_source) = cluster.power_source {
+                    return power_source.is_mains();
+                }
+            }
+        }
+        self.power_source.unwrap_or(false)
+    }
+
+    pub fn battery_level(&self) -> Option<f32> {
+        for endpoint in &self.endpoints {
+            for cluster in endpoint.get_in_clusters() {
+                if let Cluster::PowerConfiguration(cluster) = cluster {
+                    if cluster.battery_percentage.is_some() {
+                        return cluster.battery_percentage;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn add_binding(&mut self, binding: Binding) {
+        if !self.bindings.contains(&binding) {
+            self.bindings.push(binding);
+        }
+    }
+
+    pub fn remove_binding(&mut self, binding: &Binding) {
+        self.bindings.retain(|b| b != binding);
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Endpoint {
     pub id: u8,
     pub in_clusters: Vec<Cluster>,
     pub out_clusters: Vec<Cluster>,
+    pub profile: Option<u16>,
+    pub device_id: Option<u16>,
+    pub version: Option<u8>,
+    pub descriptor_known: bool,
+}
+
+pub const PROFILE_HA: u16 = 0x0104;
+pub const PROFILE_ZLL: u16 = 0xc05e;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DeviceType {
+    OnOffSwitch,
+    LevelControlSwitch,
+    OnOffOutput,
+    LevelControllableOutput,
+    SceneSelector,
+    ConfigurationTool,
+    RemoteControl,
+    CombinedInterface,
+    RangeExtender,
+    MainsPowerOutlet,
+    DoorLock,
+    DoorLockController,
+    SimpleSensor,
+    ConsumptionAwarenessDevice,
+    HomeGateway,
+    SmartPlug,
+    WhiteGoods,
+    MeterInterface,
+    OnOffLight,
+    DimmableLight,
+    ColorDimmableLight,
+    OnOffLightSwitch,
+    DimmerSwitch,
+    ColorDimmerSwitch,
+    LightSensor,
+    OccupancySensor,
+    ColorTemperatureLight,
+    ExtendedColorLight,
+    Shade,
+    ShadeController,
+    WindowCoveringDevice,
+    WindowCoveringController,
+    HeatingCoolingUnit,
+    Thermostat,
+    TemperatureSensor,
+    IasCie,
+    IasAce,
+    IasZone,
+    IasWarningDevice,
+    // ZLL only
+    OnOffPlugInUnit,
+    DimmablePlugInUnit,
+    ColorLight,
+    ColorController,
+    ColorSceneController,
+    NonColorController,
+    NonColorSceneController,
+    ControlBridge,
+    OnOffSensor,
+    Other(u16, u16), // profile, device id
+}
+
+impl DeviceType {
+    pub fn from_ids(profile: u16, device_id: u16) -> Self {
+        match (profile, device_id) {
+            (PROFILE_HA, 0x0000) => Self::OnOffSwitch,
+            (PROFILE_HA, 0x0001) => Self::LevelControlSwitch,
+            (PROFILE_HA, 0x0002) => Self::OnOffOutput,
+            (PROFILE_HA, 0x0003) => Self::LevelControllableOutput,
+            (PROFILE_HA, 0x0004) => Self::SceneSelector,
+            (PROFILE_HA, 0x0005) => Self::ConfigurationTool,
+            (PROFILE_HA, 0x0006) => Self::RemoteControl,
+            (PROFILE_HA, 0x0007) => Self::CombinedInterface,
+            (PROFILE_HA, 0x0008) => Self::RangeExtender,
+            (PROFILE_HA, 0x0009) => Self::MainsPowerOutlet,
+            (PROFILE_HA, 0x000a) => Self::DoorLock,
+            (PROFILE_HA, 0x000b) => Self::DoorLockController,
+            (PROFILE_HA, 0x000c) => Self::SimpleSensor,
+            (PROFILE_HA, 0x000d) => Self::ConsumptionAwarenessDevice,
+            (PROFILE_HA, 0x0050) => Self::HomeGateway,
+            (PROFILE_HA, 0x0051) => Self::SmartPlug,
+            (PROFILE_HA, 0x0052) => Self::WhiteGoods,
+            (PROFILE_HA, 0x0053) => Self::MeterInterface,
+            (PROFILE_HA, 0x0100) => Self::OnOffLight,
+            (PROFILE_HA, 0x0101) => Self::DimmableLight,
+            (PROFILE_HA, 0x0102) => Self::ColorDimmableLight,
+            (PROFILE_HA, 0x0103) => Self::OnOffLightSwitch,
+            (PROFILE_HA, 0x0104) => Self::DimmerSwitch,
+            (PROFILE_HA, 0x0105) => Self::ColorDimmerSwitch,
+            (PROFILE_HA, 0x0106) => Self::LightSensor,
+            (PROFILE_HA, 0x0107) => Self::OccupancySensor,
+            (PROFILE_HA, 0x010c) => Self::ColorTemperatureLight,
+            (PROFILE_HA, 0x010d) => Self::ExtendedColorLight,
+            (PROFILE_HA, 0x0200) => Self::Shade,
+            (PROFILE_HA, 0x0201) => Self::ShadeController,
+            (PROFILE_HA, 0x0202) => Self::WindowCoveringDevice,
+            (PROFILE_HA, 0x0203) => Self::WindowCoveringController,
+            (PROFILE_HA, 0x0300) => Self::HeatingCoolingUnit,
+            (PROFILE_HA, 0x0301) => Self::Thermostat,
+            (PROFILE_HA, 0x0302) => Self::TemperatureSensor,
+            (PROFILE_HA, 0x0400) => Self::IasCie,
+            (PROFILE_HA, 0x0401) => Self::IasAce,
+            (PROFILE_HA, 0x0402) => Self::IasZone,
+            (PROFILE_HA, 0x0403) => Self::IasWarningDevice,
+            (PROFILE_ZLL, 0x0000) => Self::OnOffLight,
+            (PROFILE_ZLL, 0x0010) => Self::OnOffPlugInUnit,
+            (PROFILE_ZLL, 0x0100) => Self::DimmableLight,
+            (PROFILE_ZLL, 0x0110) => Self::DimmablePlugInUnit,
+            (PROFILE_ZLL, 0x0200) => Self::ColorLight,
+            (PROFILE_ZLL, 0x0210) => Self::ExtendedColorLight,
+            (PROFILE_ZLL, 0x0220) => Self::ColorTemperatureLight,
+            (PROFILE_ZLL, 0x0800) => Self::ColorController,
+            (PROFILE_ZLL, 0x0810) => Self::ColorSceneController,
+            (PROFILE_ZLL, 0x0820) => Self::NonColorController,
+            (PROFILE_ZLL, 0x0830) => Self::NonColorSceneController,
+            (PROFILE_ZLL, 0x0840) => Self::ControlBridge,
+            (PROFILE_ZLL, 0x0850) => Self::OnOffSensor,
+            (profile, device_id) => Self::Other(profile, device_id),
+        }
+    }
 }
 
 impl Endpoint {
@@ -106,6 +385,18 @@ impl Endpoint {
             id,
             in_clusters: Vec::new(),
             out_clusters: Vec::new(),
+            profile: None,
+            device_id: None,
+            version: None,
+            descriptor_known: false,
+        }
+    }
+
+    /// Returns the device type, known once the simple descriptor is received.
+    pub fn device_type(&self) -> Option<DeviceType> {
+        match (self.profile, self.device_id) {
+            (Some(profile), Some(device_id)) => Some(DeviceType::from_ids(profile, device_id)),
+            _ => None,
         }
     }
 
@@ -121,7 +412,16 @@ impl Endpoint {
         &self.in_clusters
     }
 
-    fn get_mut_in_cluster(&mut self, id: u16) -> Option<&mut Cluster> {
+    pub fn get_in_cluster(&self, id: u16) -> Option<&Cluster> {
+        for cluster in &self.in_clusters {
+            if cluster.id() == id {
+                return Some(cluster);
+            }
+        }
+        None
+    }
+
+    pub fn get_mut_in_cluster(&mut self, id: u16) -> Option<&mut Cluster> {
         for cluster in &mut self.in_clusters {
             if cluster.id() == id {
                 return Some(cluster);
@@ -135,4 +435,30 @@ impl Endpoint {
             cluster.update(&msg);
         }
     }
+
+    pub fn set_attribute_info(
+        &mut self,
+        cluster_id: u16,
+        attr_id: u16,
+        data_type: u8,
+        access_control: Option<u8>,
+    ) {
+        if let Some(cluster) = self.get_mut_in_cluster(cluster_id) {
+            let attr = cluster.attributes_mut().entry(attr_id).or_default();
+            attr.data_type = data_type;
+            if access_control.is_some() {
+                attr.access_control = access_control;
+            }
+        }
+    }
+
+    pub fn get_attribute(&self, cluster_id: u16, attr_id: u16) -> Option<&Attribute> {
+        self.get_in_cluster(cluster_id)
+            .and_then(|cluster| cluster.get_attribute(attr_id))
+    }
+
+    pub fn get_value(&self, cluster_id: u16, attr_id: u16) -> Option<&ZclValue> {
+        self.get_in_cluster(cluster_id)
+            .and_then(|cluster| cluster.get_value(attr_id))
+    }
 }
@@ -0,0 +1,54 @@
+use crate::{
+    cluster::{OperationEvent, ZoneStatus},
+    device::InterviewState,
+    zcl::ZclValue,
+};
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    AttributeReport {
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_id: u16,
+        value: ZclValue,
+    },
+    RequestFailed {
+        address: Option<u16>,
+        seq_num: u8,
+        status: u8,
+    },
+    LowBattery {
+        address: u16,
+        level: f32,
+    },
+    ZoneStatusChange {
+        address: u16,
+        endpoint: u8,
+        zone_id: u8,
+        status: ZoneStatus,
+    },
+    AddressChanged {
+        ieee_address: u64,
+        old_address: u16,
+        new_address: u16,
+    },
+    DeviceLeft {
+        ieee_address: u64,
+        rejoin: bool,
+    },
+    AvailabilityChanged {
+        address: u16,
+        ieee_address: u64,
+        available: bool,
+    },
+    InterviewStateChanged {
+        address: u16,
+        state: InterviewState,
+    },
+    DoorLockOperation {
+        address: u16,
+        endpoint: u8,
+        event: OperationEvent,
+    },
+}
@@ -1,9 +1,13 @@
 #[macro_use] extern crate log;
 
 pub mod cluster;
+pub mod color;
 pub mod command;
 pub mod commands;
 pub mod device;
+pub mod event;
 pub mod responses;
 pub mod serial;
+pub mod store;
+pub mod zcl;
 pub mod zigate;
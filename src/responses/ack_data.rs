@@ -0,0 +1,39 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct AckData {
+    pub status: u8,
+    pub dst_address: u16,
+    pub dst_endpoint: u8,
+    pub cluster_id: u16,
+    pub seq_num: Option<u8>, // only sent by firmware >= 3.1d
+}
+
+impl Response for AckData {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 6 {
+            return Err("Not enough data")
+        }
+
+        let status = buf.read_u8().unwrap();
+        let dst_address = buf.read_u16().unwrap();
+        let dst_endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let seq_num = buf.read_u8().ok();
+
+        Ok(Self { status, dst_address, dst_endpoint, cluster_id, seq_num })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Ack Data : status {:#X}, dst address {:X}, dst endpoint {}, cluster {:#X}, seq_num {:?}",
+                self.status,
+                self.dst_address,
+                self.dst_endpoint,
+                self.cluster_id,
+                self.seq_num))
+    }
+}
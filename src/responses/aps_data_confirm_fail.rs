@@ -0,0 +1,51 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct ApsDataConfirmFail {
+    pub status: u8,
+    pub src_endpoint: u8,
+    pub dst_endpoint: u8,
+    pub dst_addr_mode: u8,
+    pub dst_address: u64, // short or IEEE address, depending on dst_addr_mode
+    pub seq_num: u8,
+}
+
+impl Response for ApsDataConfirmFail {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 7 {
+            return Err("Not enough data")
+        }
+
+        let status = buf.read_u8().unwrap();
+        let src_endpoint = buf.read_u8().unwrap();
+        let dst_endpoint = buf.read_u8().unwrap();
+        let dst_addr_mode = buf.read_u8().unwrap();
+        let dst_address = match dst_addr_mode {
+            3 => match buf.read_u64() {
+                Ok(dst_address) => dst_address,
+                Err(_) => return Err("Failed to read IEEE address"),
+            },
+            _ => buf.read_u16().unwrap() as u64,
+        };
+        let seq_num = match buf.read_u8() {
+            Ok(seq_num) => seq_num,
+            Err(_) => return Err("Failed to read sequence number"),
+        };
+
+        Ok(Self { status, src_endpoint, dst_endpoint, dst_addr_mode, dst_address, seq_num })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "APS Data Confirm Fail : status {:#X}, src endpoint {}, dst endpoint {}, dst addr mode {}, dst address {:X}, seq_num {}",
+                self.status,
+                self.src_endpoint,
+                self.dst_endpoint,
+                self.dst_addr_mode,
+                self.dst_address,
+                self.seq_num))
+    }
+}
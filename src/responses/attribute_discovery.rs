@@ -0,0 +1,42 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug, Clone)]
+pub struct AttributeDiscoveryResponse {
+    pub complete: bool,
+    pub data_type: u8,
+    pub attr_id: u16,
+    pub src_addr: u16,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+}
+
+impl Response for AttributeDiscoveryResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 9 {
+            return Err("Not enough data")
+        }
+
+        let complete = buf.read_u8().unwrap() != 0;
+        let data_type = buf.read_u8().unwrap();
+        let attr_id = buf.read_u16().unwrap();
+        let src_addr = buf.read_u16().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+
+        Ok(Self { complete, data_type, attr_id, src_addr, endpoint, cluster_id })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Attribute Discovery Response : addr {:X}, endpoint {}, cluster {:#X}, attr {:#X}, data type {:#X}, complete {}",
+                self.src_addr,
+                self.endpoint,
+                self.cluster_id,
+                self.attr_id,
+                self.data_type,
+                self.complete))
+    }
+}
@@ -0,0 +1,30 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct BindResponse {
+    pub seq_num: u8,
+    pub status: u8,
+}
+
+impl Response for BindResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        let seq_num = match buf.read_u8() {
+            Ok(seq_num) => seq_num,
+            Err(_) => return Err("Failed to read sequence number"),
+        };
+        let status = match buf.read_u8() {
+            Ok(status) => status,
+            Err(_) => return Err("Failed to read status"),
+        };
+
+        Ok(Self { seq_num, status })
+    }
+    fn to_string(&self) -> String {
+        String::from(
+            format!("Bind Response : seq_num {}, status {}", self.seq_num, self.status))
+    }
+}
@@ -0,0 +1,94 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+use crate::zcl::ZclHeader;
+
+#[derive(Debug)]
+pub struct DataIndication {
+    pub status: u8,
+    pub profile_id: u16,
+    pub cluster_id: u16,
+    pub src_endpoint: u8,
+    pub dst_endpoint: u8,
+    pub src_addr_mode: u8,
+    pub src_address: u64, // short or IEEE address, depending on src_addr_mode
+    pub dst_addr_mode: u8,
+    pub dst_address: u64, // short or IEEE address, depending on dst_addr_mode
+    pub payload: Vec<u8>, // the ZCL frame, header included
+}
+
+impl DataIndication {
+    /// Decodes the ZCL frame header, returns it with the command payload.
+    pub fn zcl_frame(&self) -> Result<(ZclHeader, &[u8]), &'static str> {
+        ZclHeader::parse(&self.payload)
+    }
+}
+
+/// Length of an address, depending on its mode.
+fn address_len(addr_mode: u8) -> usize {
+    match addr_mode {
+        3 => 8, // IEEE
+        _ => 2,
+    }
+}
+
+fn read_address(buf: &mut ByteBuffer, addr_mode: u8) -> u64 {
+    match addr_mode {
+        3 => buf.read_u64().unwrap(),
+        _ => buf.read_u16().unwrap() as u64,
+    }
+}
+
+impl Response for DataIndication {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 8 {
+            return Err("Not enough data")
+        }
+
+        let status = buf.read_u8().unwrap();
+        let profile_id = buf.read_u16().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let src_endpoint = buf.read_u8().unwrap();
+        let dst_endpoint = buf.read_u8().unwrap();
+        let src_addr_mode = buf.read_u8().unwrap();
+        let mut pos = 8 + address_len(src_addr_mode);
+        if cmd.data.len() < pos + 1 {
+            return Err("Not enough data")
+        }
+        let src_address = read_address(&mut buf, src_addr_mode);
+        let dst_addr_mode = buf.read_u8().unwrap();
+        pos += 1 + address_len(dst_addr_mode);
+        // the payload is not length prefixed, it runs until the LQI
+        if cmd.data.len() < pos + 1 {
+            return Err("Not enough data")
+        }
+        let dst_address = read_address(&mut buf, dst_addr_mode);
+        let payload = cmd.data[pos..cmd.data.len() - 1].to_vec();
+
+        Ok(Self {
+            status,
+            profile_id,
+            cluster_id,
+            src_endpoint,
+            dst_endpoint,
+            src_addr_mode,
+            src_address,
+            dst_addr_mode,
+            dst_address,
+            payload,
+        })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Data Indication : status {}, profile {:#X}, cluster {:#X}, addr {:X}, endpoint {} -> {}, payload {:X?}",
+                self.status,
+                self.profile_id,
+                self.cluster_id,
+                self.src_address,
+                self.src_endpoint,
+                self.dst_endpoint,
+                self.payload))
+    }
+}
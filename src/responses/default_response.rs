@@ -0,0 +1,39 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct DefaultResponse {
+    pub seq_num: u8,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+    pub command_id: u8,
+    pub status: u8,
+}
+
+impl Response for DefaultResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 6 {
+            return Err("Not enough data")
+        }
+
+        let seq_num = buf.read_u8().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let command_id = buf.read_u8().unwrap();
+        let status = buf.read_u8().unwrap();
+
+        Ok(Self { seq_num, endpoint, cluster_id, command_id, status })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Default Response : seq_num {}, endpoint {}, cluster {:#X}, command {:#X}, status {:#X}",
+                self.seq_num,
+                self.endpoint,
+                self.cluster_id,
+                self.command_id,
+                self.status))
+    }
+}
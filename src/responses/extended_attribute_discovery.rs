@@ -0,0 +1,45 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug, Clone)]
+pub struct ExtendedAttributeDiscoveryResponse {
+    pub complete: bool,
+    pub data_type: u8,
+    pub attr_id: u16,
+    pub access_control: u8,
+    pub src_addr: u16,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+}
+
+impl Response for ExtendedAttributeDiscoveryResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 10 {
+            return Err("Not enough data")
+        }
+
+        let complete = buf.read_u8().unwrap() != 0;
+        let data_type = buf.read_u8().unwrap();
+        let attr_id = buf.read_u16().unwrap();
+        let access_control = buf.read_u8().unwrap();
+        let src_addr = buf.read_u16().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+
+        Ok(Self { complete, data_type, attr_id, access_control, src_addr, endpoint, cluster_id })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Extended Attribute Discovery Response : addr {:X}, endpoint {}, cluster {:#X}, attr {:#X}, data type {:#X}, access control {:#b}, complete {}",
+                self.src_addr,
+                self.endpoint,
+                self.cluster_id,
+                self.attr_id,
+                self.data_type,
+                self.access_control,
+                self.complete))
+    }
+}
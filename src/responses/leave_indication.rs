@@ -0,0 +1,30 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct LeaveIndication {
+    pub ieee_address: u64,
+    pub rejoin: bool,
+}
+
+impl Response for LeaveIndication {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 9 {
+            return Err("Not enough data")
+        }
+
+        let ieee_address = buf.read_u64().unwrap();
+        let rejoin = buf.read_u8().unwrap() != 0;
+
+        Ok(Self { ieee_address, rejoin })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Leave Indication : IEEE address {:X}, rejoin {}",
+                self.ieee_address,
+                self.rejoin))
+    }
+}
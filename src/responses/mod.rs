@@ -25,12 +25,23 @@ macro_rules! make_response_box {
             }
             pub fn from_command(cmd: &Command) -> ResponseBox {
                 match FromPrimitive::from_u16(cmd.msg_type) {
-                    $( Some(MessageType::$resp) => ResponseBox::$box($resp::from_command(&cmd).unwrap()), )+
-                    Some(MessageType::ReportIndividualAttributResponse) => ResponseBox::ReportIndividualAttributResponseBox(ReadAttributeResponse::from_command(&cmd).unwrap()),
+                    // a malformed frame must not bring down the receiver
+                    $( Some(MessageType::$resp) => match $resp::from_command(&cmd) {
+                        Ok(response) => ResponseBox::$box(response),
+                        Err(err) => Self::malformed(&cmd, err),
+                    }, )+
+                    Some(MessageType::ReportIndividualAttributResponse) => match ReadAttributeResponse::from_command(&cmd) {
+                        Ok(response) => ResponseBox::ReportIndividualAttributResponseBox(response),
+                        Err(err) => Self::malformed(&cmd, err),
+                    },
                     Some(_) => ResponseBox::UnknownBox(Unknown::from_command(&cmd).unwrap()),
                     None => ResponseBox::UnknownBox(Unknown::from_command(&cmd).unwrap()),
                 }
             }
+            fn malformed(cmd: &Command, err: &str) -> ResponseBox {
+                warn!("Malformed {}: {}", cmd, err);
+                ResponseBox::UnknownBox(Unknown::from_command(&cmd).unwrap())
+            }
         }
     }
 }
@@ -42,7 +53,21 @@ make_response_box!(
     active_endpoints, ActiveEndpointsBox(ActiveEndpoints),
     simple_descriptor, SimpleDescriptorResponseBox(SimpleDescriptorResponse),
     version_list, VersionListBox(VersionList),
-    read_attribute, ReadAttributeResponseBox(ReadAttributeResponse)
+    read_attribute, ReadAttributeResponseBox(ReadAttributeResponse),
+    network_state, NetworkStateResponseBox(NetworkStateResponse),
+    bind, BindResponseBox(BindResponse),
+    unbind, UnbindResponseBox(UnbindResponse),
+    attribute_discovery, AttributeDiscoveryResponseBox(AttributeDiscoveryResponse),
+    extended_attribute_discovery, ExtendedAttributeDiscoveryResponseBox(ExtendedAttributeDiscoveryResponse),
+    default_response, DefaultResponseBox(DefaultResponse),
+    aps_data_confirm_fail, ApsDataConfirmFailBox(ApsDataConfirmFail),
+    ack_data, AckDataBox(AckData),
+    zone_enroll_request, ZoneEnrollRequestBox(ZoneEnrollRequest),
+    zone_status_change, ZoneStatusChangeNotificationBox(ZoneStatusChangeNotification),
+    write_attribute, WriteAttributeResponseBox(WriteAttributeResponse),
+    management_leave, ManagementLeaveResponseBox(ManagementLeaveResponse),
+    leave_indication, LeaveIndicationBox(LeaveIndication),
+    data_indication, DataIndicationBox(DataIndication)
     );
 
 pub use devices_list::Device;
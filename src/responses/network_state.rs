@@ -0,0 +1,39 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct NetworkStateResponse {
+    pub short_address: u16,
+    pub ieee_address: u64,
+    pub pan_id: u16,
+    pub ext_pan_id: u64,
+    pub channel: u8,
+}
+
+impl Response for NetworkStateResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 21 {
+            return Err("Not enough data")
+        }
+
+        let short_address = buf.read_u16().unwrap();
+        let ieee_address = buf.read_u64().unwrap();
+        let pan_id = buf.read_u16().unwrap();
+        let ext_pan_id = buf.read_u64().unwrap();
+        let channel = buf.read_u8().unwrap();
+
+        Ok(Self { short_address, ieee_address, pan_id, ext_pan_id, channel })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Network State : short address {:X}, IEEE address {:X}, PAN ID {:X}, extended PAN ID {:X}, channel {}",
+                self.short_address,
+                self.ieee_address,
+                self.pan_id,
+                self.ext_pan_id,
+                self.channel))
+    }
+}
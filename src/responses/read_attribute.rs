@@ -3,6 +3,7 @@ use std::str;
 
 use crate::command::Command;
 use crate::responses::Response;
+use crate::zcl::ZclValue;
 
 pub struct ReadAttributeResponse {
     pub seq_num: u8,
@@ -52,6 +53,12 @@ impl Response for ReadAttributeResponse {
 }
 
 impl ReadAttributeResponse {
+    pub fn value(&self) -> Result<ZclValue, &'static str> {
+        if self.attr_status != 0 {
+            return Err("Attribute status is not success");
+        }
+        ZclValue::from_bytes(self.attr_data_type, &self.data)
+    }
     pub fn data_as_u8(&self) -> Result<u8, ()> {
         if self.data.len() < 1 {
             error!("Failed to read attribute as u8: not enough data.");
@@ -0,0 +1,41 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct WriteAttributeResponse {
+    pub seq_num: u8,
+    pub src_addr: u16,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+    pub attr_enum: u16,
+    pub attr_status: u8,
+}
+
+impl Response for WriteAttributeResponse {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 9 {
+            return Err("Not enough data")
+        }
+
+        let seq_num = buf.read_u8().unwrap();
+        let src_addr = buf.read_u16().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let attr_enum = buf.read_u16().unwrap();
+        let attr_status = buf.read_u8().unwrap();
+
+        Ok(Self { seq_num, src_addr, endpoint, cluster_id, attr_enum, attr_status })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Write Attribute Response : addr {:X}, endpoint {}, cluster {:#X}, attr enum {:#X}, status {:#X}",
+                self.src_addr,
+                self.endpoint,
+                self.cluster_id,
+                self.attr_enum,
+                self.attr_status))
+    }
+}
@@ -0,0 +1,64 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct ZoneEnrollRequest {
+    pub seq_num: u8,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+    pub src_addr_mode: u8,
+    pub src_address: u64, // short or IEEE address, depending on src_addr_mode
+    pub zone_type: u16,
+    pub manufacturer_code: u16,
+}
+
+impl Response for ZoneEnrollRequest {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 5 {
+            return Err("Not enough data")
+        }
+
+        let seq_num = buf.read_u8().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let src_addr_mode = buf.read_u8().unwrap();
+        // the address is followed by the zone type and manufacturer code
+        let addr_len = if src_addr_mode == 3 { 8 } else { 2 };
+        if cmd.data.len() < 5 + addr_len + 4 {
+            return Err("Not enough data")
+        }
+        let src_address = match src_addr_mode {
+            3 => buf.read_u64().unwrap(),
+            _ => buf.read_u16().unwrap() as u64,
+        };
+        let zone_type = match buf.read_u16() {
+            Ok(zone_type) => zone_type,
+            Err(_) => return Err("Failed to read zone type"),
+        };
+        let manufacturer_code = match buf.read_u16() {
+            Ok(manufacturer_code) => manufacturer_code,
+            Err(_) => return Err("Failed to read manufacturer code"),
+        };
+
+        Ok(Self {
+            seq_num,
+            endpoint,
+            cluster_id,
+            src_addr_mode,
+            src_address,
+            zone_type,
+            manufacturer_code,
+        })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Zone Enroll Request : addr {:X}, endpoint {}, zone type {:#X}, manufacturer code {:#X}",
+                self.src_address,
+                self.endpoint,
+                self.zone_type,
+                self.manufacturer_code))
+    }
+}
@@ -0,0 +1,78 @@
+use bytebuffer::ByteBuffer;
+
+use crate::responses::Response;
+use crate::command::Command;
+
+#[derive(Debug)]
+pub struct ZoneStatusChangeNotification {
+    pub seq_num: u8,
+    pub endpoint: u8,
+    pub cluster_id: u16,
+    pub src_addr_mode: u8,
+    pub src_address: u64, // short or IEEE address, depending on src_addr_mode
+    pub zone_status: u16,
+    pub extended_status: u8,
+    pub zone_id: u8,
+    pub delay: u16,
+}
+
+impl Response for ZoneStatusChangeNotification {
+    fn from_command(cmd: &Command) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(&cmd.data);
+        if cmd.data.len() < 5 {
+            return Err("Not enough data")
+        }
+
+        let seq_num = buf.read_u8().unwrap();
+        let endpoint = buf.read_u8().unwrap();
+        let cluster_id = buf.read_u16().unwrap();
+        let src_addr_mode = buf.read_u8().unwrap();
+        // the address is followed by the zone status, extended status, zone id and delay
+        let addr_len = if src_addr_mode == 3 { 8 } else { 2 };
+        if cmd.data.len() < 5 + addr_len + 6 {
+            return Err("Not enough data")
+        }
+        let src_address = match src_addr_mode {
+            3 => buf.read_u64().unwrap(),
+            _ => buf.read_u16().unwrap() as u64,
+        };
+        let zone_status = match buf.read_u16() {
+            Ok(zone_status) => zone_status,
+            Err(_) => return Err("Failed to read zone status"),
+        };
+        let extended_status = match buf.read_u8() {
+            Ok(extended_status) => extended_status,
+            Err(_) => return Err("Failed to read extended status"),
+        };
+        let zone_id = match buf.read_u8() {
+            Ok(zone_id) => zone_id,
+            Err(_) => return Err("Failed to read zone id"),
+        };
+        let delay = match buf.read_u16() {
+            Ok(delay) => delay,
+            Err(_) => return Err("Failed to read delay"),
+        };
+
+        Ok(Self {
+            seq_num,
+            endpoint,
+            cluster_id,
+            src_addr_mode,
+            src_address,
+            zone_status,
+            extended_status,
+            zone_id,
+            delay,
+        })
+    }
+    fn to_string(&self) -> String {
+        String::from(format!(
+                "Zone Status Change Notification : addr {:X}, endpoint {}, zone status {:#b}, extended status {}, zone id {}, delay {}",
+                self.src_address,
+                self.endpoint,
+                self.zone_status,
+                self.extended_status,
+                self.zone_id,
+                self.delay))
+    }
+}
@@ -0,0 +1,50 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::device::Device;
+
+/// Keeps the device database between restarts.
+pub trait DeviceStore: Send {
+    fn load(&mut self) -> io::Result<Vec<Device>>;
+    fn save(&mut self, devices: &[Device]) -> io::Result<()>;
+}
+
+/// Stores the devices in a JSON file, as an object keyed by IEEE address.
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+impl DeviceStore for JsonFileStore {
+    fn load(&mut self) -> io::Result<Vec<Device>> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let devices: BTreeMap<String, Device> = serde_json::from_str(&json)?;
+        Ok(devices.into_iter().map(|(_, device)| device).collect())
+    }
+
+    fn save(&mut self, devices: &[Device]) -> io::Result<()> {
+        let devices: BTreeMap<String, &Device> = devices
+            .iter()
+            .map(|device| (format!("{:016x}", device.ieee_address), device))
+            .collect();
+        let json = serde_json::to_string_pretty(&devices)?;
+        // a crash while writing must not leave a truncated database
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
@@ -0,0 +1,438 @@
+use bytebuffer::ByteBuffer;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ZclValue {
+    Null,
+    Data(Vec<u8>),
+    Bool(bool),
+    Bitmap8(u8),
+    Bitmap16(u16),
+    Bitmap24(u32),
+    Bitmap32(u32),
+    Bitmap40(u64),
+    Bitmap48(u64),
+    Bitmap56(u64),
+    Bitmap64(u64),
+    Uint8(u8),
+    Uint16(u16),
+    Uint24(u32),
+    Uint32(u32),
+    Uint40(u64),
+    Uint48(u64),
+    Uint56(u64),
+    Uint64(u64),
+    Int8(i8),
+    Int16(i16),
+    Int24(i32),
+    Int32(i32),
+    Int40(i64),
+    Int48(i64),
+    Int56(i64),
+    Int64(i64),
+    Enum8(u8),
+    Enum16(u16),
+    SemiFloat(f32),
+    SingleFloat(f32),
+    DoubleFloat(f64),
+    OctetString(Vec<u8>),
+    CharString(String),
+    Array(Vec<ZclValue>),
+    Struct(Vec<ZclValue>),
+    UtcTime(u32),
+    ClusterId(u16),
+    AttributeId(u16),
+    IeeeAddress(u64),
+    Unknown(u8, Vec<u8>),
+}
+
+fn read_uint(buf: &mut ByteBuffer, len: usize) -> Result<u64, &'static str> {
+    let bytes = match buf.read_bytes(len) {
+        Ok(bytes) => bytes,
+        Err(_) => return Err("Not enough data"),
+    };
+    Ok(bytes.iter().fold(0u64, |acc, byte| acc << 8 | *byte as u64))
+}
+
+fn read_int(buf: &mut ByteBuffer, len: usize) -> Result<i64, &'static str> {
+    let shift = 64 - 8 * len;
+    let value = read_uint(buf, len)?;
+    Ok(((value << shift) as i64) >> shift)
+}
+
+fn semi_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+fn f32_to_semi(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = ((bits >> 13) & 0x3ff) as u16;
+    if value.is_nan() {
+        0x7e00
+    } else if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        sign
+    } else {
+        sign | (exponent as u16) << 10 | mantissa
+    }
+}
+
+impl ZclValue {
+    /// Decodes an attribute value as sent by the ZiGate. Top level strings are
+    /// not length prefixed, the whole data is the string.
+    pub fn from_bytes(data_type: u8, data: &[u8]) -> Result<Self, &'static str> {
+        let mut buf = ByteBuffer::from_bytes(data);
+        Self::read(data_type, &mut buf, Some(data.len()))
+    }
+
+    fn read(data_type: u8, buf: &mut ByteBuffer, len: Option<usize>) -> Result<Self, &'static str> {
+        let value = match data_type {
+            0x00 => Self::Null,
+            0x08..=0x0f => {
+                let size = (data_type - 0x07) as usize;
+                match buf.read_bytes(size) {
+                    Ok(bytes) => Self::Data(bytes),
+                    Err(_) => return Err("Not enough data"),
+                }
+            }
+            0x10 => Self::Bool(read_uint(buf, 1)? != 0),
+            0x18 => Self::Bitmap8(read_uint(buf, 1)? as u8),
+            0x19 => Self::Bitmap16(read_uint(buf, 2)? as u16),
+            0x1a => Self::Bitmap24(read_uint(buf, 3)? as u32),
+            0x1b => Self::Bitmap32(read_uint(buf, 4)? as u32),
+            0x1c => Self::Bitmap40(read_uint(buf, 5)?),
+            0x1d => Self::Bitmap48(read_uint(buf, 6)?),
+            0x1e => Self::Bitmap56(read_uint(buf, 7)?),
+            0x1f => Self::Bitmap64(read_uint(buf, 8)?),
+            0x20 => Self::Uint8(read_uint(buf, 1)? as u8),
+            0x21 => Self::Uint16(read_uint(buf, 2)? as u16),
+            0x22 => Self::Uint24(read_uint(buf, 3)? as u32),
+            0x23 => Self::Uint32(read_uint(buf, 4)? as u32),
+            0x24 => Self::Uint40(read_uint(buf, 5)?),
+            0x25 => Self::Uint48(read_uint(buf, 6)?),
+            0x26 => Self::Uint56(read_uint(buf, 7)?),
+            0x27 => Self::Uint64(read_uint(buf, 8)?),
+            0x28 => Self::Int8(read_int(buf, 1)? as i8),
+            0x29 => Self::Int16(read_int(buf, 2)? as i16),
+            0x2a => Self::Int24(read_int(buf, 3)? as i32),
+            0x2b => Self::Int32(read_int(buf, 4)? as i32),
+            0x2c => Self::Int40(read_int(buf, 5)?),
+            0x2d => Self::Int48(read_int(buf, 6)?),
+            0x2e => Self::Int56(read_int(buf, 7)?),
+            0x2f => Self::Int64(read_int(buf, 8)?),
+            0x30 => Self::Enum8(read_uint(buf, 1)? as u8),
+            0x31 => Self::Enum16(read_uint(buf, 2)? as u16),
+            0x38 => Self::SemiFloat(semi_to_f32(read_uint(buf, 2)? as u16)),
+            0x39 => Self::SingleFloat(f32::from_bits(read_uint(buf, 4)? as u32)),
+            0x3a => Self::DoubleFloat(f64::from_bits(read_uint(buf, 8)?)),
+            0x41..=0x44 => {
+                let size = match (len, data_type) {
+                    (Some(len), _) => len,
+                    (None, 0x41) | (None, 0x42) => read_uint(buf, 1)? as usize,
+                    (None, _) => read_uint(buf, 2)? as usize,
+                };
+                let bytes = match buf.read_bytes(size) {
+                    Ok(bytes) => bytes,
+                    Err(_) => return Err("Not enough data"),
+                };
+                match data_type {
+                    0x42 | 0x44 => match String::from_utf8(bytes) {
+                        Ok(s) => Self::CharString(s.trim_end_matches('\0').into()),
+                        Err(_) => return Err("Invalid UTF-8"),
+                    },
+                    _ => Self::OctetString(bytes),
+                }
+            }
+            0x48 | 0x50 | 0x51 => {
+                let elem_type = read_uint(buf, 1)? as u8;
+                let count = read_uint(buf, 2)?;
+                let mut elems = Vec::new();
+                for _ in 0..count {
+                    elems.push(Self::read(elem_type, buf, None)?);
+                }
+                Self::Array(elems)
+            }
+            0x4c => {
+                let count = read_uint(buf, 2)?;
+                let mut elems = Vec::new();
+                for _ in 0..count {
+                    let elem_type = read_uint(buf, 1)? as u8;
+                    elems.push(Self::read(elem_type, buf, None)?);
+                }
+                Self::Struct(elems)
+            }
+            0xe2 => Self::UtcTime(read_uint(buf, 4)? as u32),
+            0xe8 => Self::ClusterId(read_uint(buf, 2)? as u16),
+            0xe9 => Self::AttributeId(read_uint(buf, 2)? as u16),
+            0xf0 => Self::IeeeAddress(read_uint(buf, 8)?),
+            _ => {
+                let size = len.unwrap_or(0);
+                match buf.read_bytes(size) {
+                    Ok(bytes) => Self::Unknown(data_type, bytes),
+                    Err(_) => return Err("Not enough data"),
+                }
+            }
+        };
+        Ok(value)
+    }
+
+    pub fn data_type(&self) -> u8 {
+        match self {
+            Self::Null => 0x00,
+            Self::Data(data) => 0x07 + data.len().clamp(1, 8) as u8,
+            Self::Bool(_) => 0x10,
+            Self::Bitmap8(_) => 0x18,
+            Self::Bitmap16(_) => 0x19,
+            Self::Bitmap24(_) => 0x1a,
+            Self::Bitmap32(_) => 0x1b,
+            Self::Bitmap40(_) => 0x1c,
+            Self::Bitmap48(_) => 0x1d,
+            Self::Bitmap56(_) => 0x1e,
+            Self::Bitmap64(_) => 0x1f,
+            Self::Uint8(_) => 0x20,
+            Self::Uint16(_) => 0x21,
+            Self::Uint24(_) => 0x22,
+            Self::Uint32(_) => 0x23,
+            Self::Uint40(_) => 0x24,
+            Self::Uint48(_) => 0x25,
+            Self::Uint56(_) => 0x26,
+            Self::Uint64(_) => 0x27,
+            Self::Int8(_) => 0x28,
+            Self::Int16(_) => 0x29,
+            Self::Int24(_) => 0x2a,
+            Self::Int32(_) => 0x2b,
+            Self::Int40(_) => 0x2c,
+            Self::Int48(_) => 0x2d,
+            Self::Int56(_) => 0x2e,
+            Self::Int64(_) => 0x2f,
+            Self::Enum8(_) => 0x30,
+            Self::Enum16(_) => 0x31,
+            Self::SemiFloat(_) => 0x38,
+            Self::SingleFloat(_) => 0x39,
+            Self::DoubleFloat(_) => 0x3a,
+            Self::OctetString(_) => 0x41,
+            Self::CharString(_) => 0x42,
+            Self::Array(_) => 0x48,
+            Self::Struct(_) => 0x4c,
+            Self::UtcTime(_) => 0xe2,
+            Self::ClusterId(_) => 0xe8,
+            Self::AttributeId(_) => 0xe9,
+            Self::IeeeAddress(_) => 0xf0,
+            Self::Unknown(data_type, _) => *data_type,
+        }
+    }
+
+    /// Encodes the value the way the ZiGate expects it in a write attribute request.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let uint = |value: u64, len: usize| value.to_be_bytes()[8 - len..].to_vec();
+        match self {
+            Self::Null => vec![],
+            Self::Data(data) | Self::Unknown(_, data) => data.clone(),
+            Self::Bool(value) => vec![*value as u8],
+            Self::Bitmap8(value) | Self::Uint8(value) | Self::Enum8(value) => vec![*value],
+            Self::Bitmap16(value)
+            | Self::Uint16(value)
+            | Self::Enum16(value)
+            | Self::ClusterId(value)
+            | Self::AttributeId(value) => value.to_be_bytes().to_vec(),
+            Self::Bitmap24(value) | Self::Uint24(value) => uint(*value as u64, 3),
+            Self::Bitmap32(value) | Self::Uint32(value) | Self::UtcTime(value) => {
+                value.to_be_bytes().to_vec()
+            }
+            Self::Bitmap40(value) | Self::Uint40(value) => uint(*value, 5),
+            Self::Bitmap48(value) | Self::Uint48(value) => uint(*value, 6),
+            Self::Bitmap56(value) | Self::Uint56(value) => uint(*value, 7),
+            Self::Bitmap64(value) | Self::Uint64(value) | Self::IeeeAddress(value) => {
+                value.to_be_bytes().to_vec()
+            }
+            Self::Int8(value) => vec![*value as u8],
+            Self::Int16(value) => value.to_be_bytes().to_vec(),
+            Self::Int24(value) => uint(*value as u64, 3),
+            Self::Int32(value) => value.to_be_bytes().to_vec(),
+            Self::Int40(value) => uint(*value as u64, 5),
+            Self::Int48(value) => uint(*value as u64, 6),
+            Self::Int56(value) => uint(*value as u64, 7),
+            Self::Int64(value) => value.to_be_bytes().to_vec(),
+            Self::SemiFloat(value) => f32_to_semi(*value).to_be_bytes().to_vec(),
+            Self::SingleFloat(value) => value.to_bits().to_be_bytes().to_vec(),
+            Self::DoubleFloat(value) => value.to_bits().to_be_bytes().to_vec(),
+            Self::OctetString(data) => {
+                let mut bytes = vec![data.len() as u8];
+                bytes.extend(data);
+                bytes
+            }
+            Self::CharString(data) => {
+                let mut bytes = vec![data.len() as u8];
+                bytes.extend(data.as_bytes());
+                bytes
+            }
+            Self::Array(elems) => {
+                let elem_type = elems.first().map_or(0, |elem| elem.data_type());
+                let mut bytes = vec![elem_type];
+                bytes.extend(&(elems.len() as u16).to_be_bytes());
+                for elem in elems {
+                    bytes.extend(elem.to_bytes());
+                }
+                bytes
+            }
+            Self::Struct(elems) => {
+                let mut bytes = (elems.len() as u16).to_be_bytes().to_vec();
+                for elem in elems {
+                    bytes.push(elem.data_type());
+                    bytes.extend(elem.to_bytes());
+                }
+                bytes
+            }
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => self.as_u64().map(|value| value != 0),
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Bitmap8(value) | Self::Uint8(value) | Self::Enum8(value) => Some(*value as u64),
+            Self::Bitmap16(value)
+            | Self::Uint16(value)
+            | Self::Enum16(value)
+            | Self::ClusterId(value)
+            | Self::AttributeId(value) => Some(*value as u64),
+            Self::Bitmap24(value)
+            | Self::Bitmap32(value)
+            | Self::Uint24(value)
+            | Self::Uint32(value)
+            | Self::UtcTime(value) => Some(*value as u64),
+            Self::Bitmap40(value)
+            | Self::Bitmap48(value)
+            | Self::Bitmap56(value)
+            | Self::Bitmap64(value)
+            | Self::Uint40(value)
+            | Self::Uint48(value)
+            | Self::Uint56(value)
+            | Self::Uint64(value)
+            | Self::IeeeAddress(value) => Some(*value),
+            _ => self.as_i64().and_then(|value| u64::try_from(value).ok()),
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int8(value) => Some(*value as i64),
+            Self::Int16(value) => Some(*value as i64),
+            Self::Int24(value) | Self::Int32(value) => Some(*value as i64),
+            Self::Int40(value) | Self::Int48(value) | Self::Int56(value) | Self::Int64(value) => {
+                Some(*value)
+            }
+            Self::Bool(_) | Self::Null | Self::Data(_) | Self::Unknown(_, _) => None,
+            Self::SemiFloat(_) | Self::SingleFloat(_) | Self::DoubleFloat(_) => None,
+            Self::OctetString(_) | Self::CharString(_) => None,
+            Self::Array(_) | Self::Struct(_) => None,
+            _ => self.as_u64().and_then(|value| i64::try_from(value).ok()),
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::SemiFloat(value) | Self::SingleFloat(value) => Some(*value as f64),
+            Self::DoubleFloat(value) => Some(*value),
+            _ => self.as_i64().map(|value| value as f64),
+        }
+    }
+
+    pub fn as_u8(&self) -> Option<u8> {
+        self.as_u64().and_then(|value| u8::try_from(value).ok())
+    }
+
+    pub fn as_u16(&self) -> Option<u16> {
+        self.as_u64().and_then(|value| u16::try_from(value).ok())
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        self.as_u64().and_then(|value| u32::try_from(value).ok())
+    }
+
+    pub fn as_i16(&self) -> Option<i16> {
+        self.as_i64().and_then(|value| i16::try_from(value).ok())
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::CharString(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Frame control field of a ZCL frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameControl {
+    pub cluster_specific: bool, // false for profile wide (global) commands
+    pub manufacturer_specific: bool,
+    pub server_to_client: bool,
+    pub disable_default_response: bool,
+}
+
+impl FrameControl {
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            cluster_specific: (bits & 0x03) == 0x01,
+            manufacturer_specific: (bits & 0x04) != 0,
+            server_to_client: (bits & 0x08) != 0,
+            disable_default_response: (bits & 0x10) != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZclHeader {
+    pub frame_control: FrameControl,
+    pub manufacturer_code: Option<u16>,
+    pub seq_num: u8,
+    pub command_id: u8,
+}
+
+impl ZclHeader {
+    /// Decodes the header of a ZCL frame, returns it with the command payload that follows.
+    pub fn parse(frame: &[u8]) -> Result<(Self, &[u8]), &'static str> {
+        if frame.is_empty() {
+            return Err("Not enough data");
+        }
+        let frame_control = FrameControl::from_bits(frame[0]);
+        let header_len = if frame_control.manufacturer_specific {
+            5
+        } else {
+            3
+        };
+        if frame.len() < header_len {
+            return Err("Not enough data");
+        }
+        // the manufacturer code is sent over the air in little endian
+        let manufacturer_code = match frame_control.manufacturer_specific {
+            true => Some(u16::from_le_bytes([frame[1], frame[2]])),
+            false => None,
+        };
+        let header = Self {
+            frame_control,
+            manufacturer_code,
+            seq_num: frame[header_len - 2],
+            command_id: frame[header_len - 1],
+        };
+        Ok((header, &frame[header_len..]))
+    }
+}
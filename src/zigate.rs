@@ -1,19 +1,32 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::{mpsc::Receiver, Arc, Mutex},
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
     thread,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 use crate::{
-    cluster::{Cluster, ColorCapabilities},
+    cluster::{
+        Cluster, ColorCapabilities, ColorMode, LockState, OperationEvent, ZoneStatus, C0000, C0300,
+    },
+    color::{self, Rgb, Xy},
     command::{Command, MessageType},
     commands,
-    device::Device,
+    commands::{
+        AttributeDiscovery, ColorLoopSet, ColorMoveMode, ColorStepMode, ColorTempStep,
+        CoverCommand, Destination, Effect, LevelMove, LevelStep, LevelStop, SetpointMode,
+    },
+    device::{Binding, Device, InterviewState, INTERVIEW_BASIC_ATTRIBUTES},
+    event::Event,
     responses,
     responses::{Response, ResponseBox},
     serial::{uart_recver, UartSender},
+    store::DeviceStore,
+    zcl::{ZclHeader, ZclValue},
 };
 
 pub struct Zigate {
@@ -23,11 +36,261 @@ pub struct Zigate {
     version: Option<String>,
 }
 
+const DISCOVERY_PAGE_SIZE: u8 = 16;
+const DEFAULT_LOW_BATTERY_THRESHOLD: f32 = 10.0;
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+const INTERVIEW_TIMEOUT: Duration = Duration::from_secs(10);
+const INTERVIEW_MAX_ATTEMPTS: u8 = 3;
+
+/// When devices are considered unavailable, after how long without receiving anything from them.
+#[derive(Debug, Clone, Copy)]
+pub struct AvailabilityConfig {
+    pub mains_timeout: Duration,
+    pub battery_timeout: Duration,
+    /// Reads the Basic cluster of silent mains devices before marking them unavailable.
+    pub ping: bool,
+}
+
+impl Default for AvailabilityConfig {
+    fn default() -> Self {
+        Self {
+            mains_timeout: Duration::from_secs(10 * 60),
+            battery_timeout: Duration::from_secs(25 * 3600),
+            ping: true,
+        }
+    }
+}
+
 struct ZigateData {
     pub last_resp: HashMap<MessageType, Command>,
     pub last_status: HashMap<MessageType, Command>,
-    pub exp_resp: u16,
-    pub devices: HashMap<u16, Device>,
+    pub devices: HashMap<u64, Device>,
+    pub addresses: HashMap<u16, u64>, // short address to IEEE address
+    pub discovered: Vec<DiscoveredAttribute>,
+    pub listeners: Vec<Sender<Event>>,
+    pub awaiting_status: HashMap<u16, PendingRequest>,
+    pub pending: HashMap<u8, PendingRequest>,
+    pub low_battery_threshold: f32,
+    pub coordinator_ieee: Option<u64>,
+    pub next_zone_id: u8,
+    pub store: Option<Box<dyn DeviceStore>>,
+    pub availability: Option<AvailabilityConfig>,
+}
+
+impl ZigateData {
+    fn emit(&mut self, event: Event) {
+        self.listeners
+            .retain(|listener| listener.send(event.clone()).is_ok());
+    }
+
+    fn resolve_address(&self, addr_mode: u8, address: u64) -> Option<u16> {
+        match addr_mode {
+            3 => self
+                .devices
+                .get(&address)
+                .map(|device| device.short_address),
+            _ => Some(address as u16),
+        }
+    }
+
+    fn device(&self, address: u16) -> Option<&Device> {
+        self.addresses
+            .get(&address)
+            .and_then(|ieee| self.devices.get(ieee))
+    }
+
+    fn device_mut(&mut self, address: u16) -> Option<&mut Device> {
+        match self.addresses.get(&address) {
+            Some(ieee) => self.devices.get_mut(ieee),
+            None => None,
+        }
+    }
+
+    fn add_device(&mut self, mut device: Device) {
+        // the availability window of a device that was never heard of starts now
+        device.last_seen.get_or_insert_with(SystemTime::now);
+        self.addresses
+            .insert(device.short_address, device.ieee_address);
+        self.devices.insert(device.ieee_address, device);
+    }
+
+    /// Updates the short address of a known device after it rejoined, returns false if the
+    /// device is unknown.
+    fn update_address(&mut self, ieee_address: u64, new_address: u16) -> bool {
+        let device = match self.devices.get_mut(&ieee_address) {
+            Some(device) => device,
+            None => return false,
+        };
+        let old_address = device.short_address;
+        if old_address == new_address {
+            return true;
+        }
+        info!(
+            "{:016X} changed address from {:X} to {:X}",
+            ieee_address, old_address, new_address
+        );
+        device.short_address = new_address;
+        if self.addresses.get(&old_address) == Some(&ieee_address) {
+            self.addresses.remove(&old_address);
+        }
+        self.addresses.insert(new_address, ieee_address);
+        self.emit(Event::AddressChanged {
+            ieee_address,
+            old_address,
+            new_address,
+        });
+        self.save_devices();
+        true
+    }
+
+    /// Records that a frame was received from a device.
+    fn mark_seen(&mut self, address: u16, lqi: Option<u8>) {
+        let device = match self.device_mut(address) {
+            Some(device) => device,
+            None => return,
+        };
+        device.last_seen = Some(SystemTime::now());
+        device.ping_sent = None;
+        if lqi.is_some() {
+            device.link_quality = lqi;
+        }
+        if !device.available {
+            device.available = true;
+            let ieee_address = device.ieee_address;
+            info!("{:X} is available again", address);
+            self.emit(Event::AvailabilityChanged {
+                address,
+                ieee_address,
+                available: true,
+            });
+        }
+    }
+
+    fn remove_device(&mut self, ieee_address: u64) -> Option<Device> {
+        let device = self.devices.remove(&ieee_address)?;
+        if self.addresses.get(&device.short_address) == Some(&ieee_address) {
+            self.addresses.remove(&device.short_address);
+        }
+        self.save_devices();
+        Some(device)
+    }
+
+    fn save_devices(&mut self) {
+        let devices: Vec<Device> = self.devices.values().cloned().collect();
+        if let Some(store) = self.store.as_mut() {
+            if let Err(err) = store.save(&devices) {
+                error!("Failed to save devices: {}", err);
+            }
+        }
+    }
+
+    /// Loads the saved devices, the ones whose interview was interrupted are interviewed again.
+    fn restore_devices(&mut self, sender: &mut UartSender) {
+        let devices = match self.store.as_mut().map(|store| store.load()) {
+            Some(Ok(devices)) => devices,
+            Some(Err(err)) => {
+                error!("Failed to load devices: {}", err);
+                return;
+            }
+            None => return,
+        };
+        info!("{} device(s) restored", devices.len());
+        for device in devices {
+            let address = device.short_address;
+            let interrupted = device.interview_state.in_progress();
+            self.add_device(device);
+            if interrupted {
+                restart_interview(sender, self, address);
+            }
+        }
+    }
+}
+
+/// A request sent to a device, keyed by the sequence number of its status. The ZCL response
+/// carries the ZCL sequence number instead, which is the same unless the frame is raw.
+struct PendingRequest {
+    address: u16,
+    endpoint: u8,
+    cluster_id: Option<u16>,
+    zcl_seq_num: Option<u8>,
+    expects_response: bool, // a ZCL response is expected after the APS ack
+    acked: bool,
+    result: Option<Result<(), u8>>,
+}
+
+impl PendingRequest {
+    fn new(address: u16, cmd: &Command, expects_response: bool) -> Self {
+        // all the commands start with the address mode, address and endpoints
+        let endpoint = cmd.data.get(4).copied().unwrap_or(0);
+        let (cluster_id, zcl_seq_num) = match MessageType::from_u16(cmd.msg_type) {
+            MessageType::RawApsDataRequest if cmd.data.len() > 12 => {
+                let cluster_id = u16::from_be_bytes([cmd.data[5], cmd.data[6]]);
+                let zcl_seq_num = ZclHeader::parse(&cmd.data[12..])
+                    .ok()
+                    .map(|(header, _)| header.seq_num);
+                (Some(cluster_id), zcl_seq_num)
+            }
+            msg_type => (command_cluster(&msg_type), None),
+        };
+        Self {
+            address,
+            endpoint,
+            cluster_id,
+            zcl_seq_num,
+            expects_response,
+            acked: false,
+            result: None,
+        }
+    }
+
+    /// Returns true if a ZCL response from this endpoint and cluster answers the request.
+    fn answered_by(&self, seq_num: u8, endpoint: u8, cluster_id: u16) -> bool {
+        self.zcl_seq_num == Some(seq_num)
+            && self.endpoint == endpoint
+            && self.cluster_id.map_or(true, |id| id == cluster_id)
+    }
+}
+
+/// Returns the cluster the ZiGate sends an action command to.
+fn command_cluster(msg_type: &MessageType) -> Option<u16> {
+    match msg_type {
+        MessageType::IdentifySend
+        | MessageType::IdentifyQuery
+        | MessageType::IdentifyTriggerEffect => Some(0x0003),
+        MessageType::ActionOnOff
+        | MessageType::ActionOnOffTimed
+        | MessageType::ActionOnOffEffect => Some(0x0006),
+        MessageType::ActionMove
+        | MessageType::ActionMoveOnOff
+        | MessageType::ActionMoveStep
+        | MessageType::ActionMoveStopOnOff => Some(0x0008),
+        MessageType::ActionLockUnlockDoor => Some(0x0101),
+        MessageType::ActionWindowCovering => Some(0x0102),
+        MessageType::ActionMoveToHue
+        | MessageType::ActionMoveHue
+        | MessageType::ActionStepHue
+        | MessageType::ActionMoveToSaturation
+        | MessageType::ActionMoveSaturation
+        | MessageType::ActionStepSaturation
+        | MessageType::ActionMoveToHueAndSaturation
+        | MessageType::ActionMoveToColor
+        | MessageType::ActionEnhancedMoveToHue
+        | MessageType::ActionColorLoopSet
+        | MessageType::ActionMoveToColorTemp
+        | MessageType::ActionMoveColorTemp
+        | MessageType::ActionStepColorTemp => Some(0x0300),
+        _ => None,
+    }
+}
+
+struct DiscoveredAttribute {
+    address: u16,
+    endpoint: u8,
+    cluster_id: u16,
+    attr_id: u16,
+    data_type: u8,
+    access_control: Option<u8>,
+    complete: bool,
 }
 
 impl Zigate {
@@ -37,8 +300,17 @@ impl Zigate {
         let data = ZigateData {
             last_resp: HashMap::new(),
             last_status: HashMap::new(),
-            exp_resp: 0,
             devices: HashMap::new(),
+            addresses: HashMap::new(),
+            discovered: Vec::new(),
+            listeners: Vec::new(),
+            awaiting_status: HashMap::new(),
+            pending: HashMap::new(),
+            low_battery_threshold: DEFAULT_LOW_BATTERY_THRESHOLD,
+            coordinator_ieee: None,
+            next_zone_id: 0,
+            store: None,
+            availability: Some(AvailabilityConfig::default()),
         };
         let data = Arc::new(Mutex::new(data));
         let version = None;
@@ -53,29 +325,62 @@ impl Zigate {
     pub fn start(&self) -> thread::JoinHandle<()> {
         let path = self.pathbuf.as_path();
         let rx = uart_recver(path);
-        let rx_sender = UartSender::new(path);
+        let mut rx_sender = UartSender::new(path);
+        self.data.lock().unwrap().restore_devices(&mut rx_sender);
+        // the coordinator IEEE address is needed to enroll IAS zones
+        rx_sender.send(&commands::get_network_state());
         let data = self.data.clone();
         thread::spawn(move || recv_fn(rx, rx_sender, data))
     }
 
+    pub fn events(&self) -> Receiver<Event> {
+        let (tx, rx) = channel();
+        self.data.lock().unwrap().listeners.push(tx);
+        rx
+    }
+
+    /// Sets the store the devices are saved to, e.g. a `JsonFileStore`. There is none by default
+    /// and `None` disables persistence again. Must be called before `start` for the devices to be
+    /// restored from it.
+    pub fn set_store(&mut self, store: Option<Box<dyn DeviceStore>>) {
+        self.data.lock().unwrap().store = store;
+    }
+
+    pub fn save_devices(&self) {
+        self.data.lock().unwrap().save_devices();
+    }
+
+    /// Sets when devices are considered unavailable, `None` disables the availability checks.
+    pub fn set_availability(&mut self, config: Option<AvailabilityConfig>) {
+        self.data.lock().unwrap().availability = config;
+    }
+
+    /// Sets the battery percentage under which a `LowBattery` event is emitted.
+    pub fn set_low_battery_threshold(&mut self, threshold: f32) {
+        self.data.lock().unwrap().low_battery_threshold = threshold;
+    }
+
     pub fn send(&mut self, cmd: &Command) {
         debug!("send: {}", cmd);
         self.sender.send(cmd);
     }
 
-    fn wait_for_responses(&mut self) {
-        for _ in 0..50 {
-            {
-                let data = self.data.lock().unwrap();
-                if data.exp_resp == 0 {
-                    return;
-                }
+    /// Waits until no device interview is in progress, or until timeout.
+    fn wait_for_interviews(&mut self) {
+        let in_progress = |data: &ZigateData| {
+            data.devices
+                .values()
+                .filter(|device| device.interview_state.in_progress())
+                .count()
+        };
+        for _ in 0..100 {
+            if in_progress(&self.data.lock().unwrap()) == 0 {
+                return;
             }
             thread::sleep(Duration::from_millis(100));
         }
-        let mut data = self.data.lock().unwrap();
-        error!("{} message(s) lost", data.exp_resp);
-        data.exp_resp = 0;
+        let data = self.data.lock().unwrap();
+        warn!("{} interview(s) still in progress", in_progress(&data));
     }
 
     fn wait_for_response(&mut self, msg_type: &MessageType) -> Option<Command> {
@@ -97,6 +402,96 @@ impl Zigate {
         self.wait_for_response(msg_type);
     }
 
+    fn wait_for_status(&mut self, msg_type: &MessageType) -> Option<responses::Status> {
+        for _ in 0..50 {
+            {
+                let mut data = self.data.lock().unwrap();
+                if let Some(cmd) = data.last_status.remove(msg_type) {
+                    return responses::Status::from_command(&cmd).ok();
+                }
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        None
+    }
+
+    /// Sends a command to a device and waits for its default response. A delivery failure, an APS
+    /// nack or an error status is reported as an error. A device may apply a command without
+    /// answering it, an APS ack without a default response is then considered a success.
+    fn send_and_confirm(&mut self, address: u16, cmd: &Command) -> Result<(), ()> {
+        self.send_and_track(address, cmd, true)
+    }
+
+    /// Sends a command to a device and waits until its delivery is confirmed by an APS ack, for
+    /// the commands a device may not answer.
+    fn send_and_ack(&mut self, address: u16, cmd: &Command) -> Result<(), ()> {
+        self.send_and_track(address, cmd, false)
+    }
+
+    fn send_and_track(
+        &mut self,
+        address: u16,
+        cmd: &Command,
+        expects_response: bool,
+    ) -> Result<(), ()> {
+        let msg_type = MessageType::from_u16(cmd.msg_type);
+        {
+            let mut data = self.data.lock().unwrap();
+            data.last_status.remove(&msg_type);
+            let pending = PendingRequest::new(address, cmd, expects_response);
+            data.awaiting_status.insert(cmd.msg_type, pending);
+        }
+        self.send(cmd);
+        let status = self.wait_for_status(&msg_type);
+        self.data
+            .lock()
+            .unwrap()
+            .awaiting_status
+            .remove(&cmd.msg_type);
+        let seq_num = match status {
+            Some(status) if status.status == 0 => status.seq_num,
+            Some(status) => {
+                error!("{} failed: {}", cmd, status.to_string());
+                return Err(());
+            }
+            None => {
+                error!("{} failed: no status received", cmd);
+                return Err(());
+            }
+        };
+        let mut result = None;
+        let mut acked = false;
+        for _ in 0..50 {
+            {
+                let data = self.data.lock().unwrap();
+                if let Some(pending) = data.pending.get(&seq_num) {
+                    acked = pending.acked;
+                    result = pending.result;
+                }
+            }
+            if result.is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        self.data.lock().unwrap().pending.remove(&seq_num);
+        match result {
+            Some(Ok(())) => Ok(()),
+            Some(Err(status)) => {
+                error!("{} to {:X} failed with status {:#X}", cmd, address, status);
+                Err(())
+            }
+            None if acked => {
+                debug!("{} to {:X} was acked but not answered", cmd, address);
+                Ok(())
+            }
+            None => {
+                error!("{} to {:X} was not confirmed", cmd, address);
+                Err(())
+            }
+        }
+    }
+
     fn remove_last_response(&mut self, msg_type: &MessageType) -> Option<Command> {
         let mut data = self.data.lock().unwrap();
         let last_status = data.last_status.remove(msg_type);
@@ -133,18 +528,625 @@ impl Zigate {
         }
     }
 
-    pub fn get_devices(&mut self) -> HashMap<u16, Device> {
+    pub fn get_coordinator_ieee(&mut self) -> Result<u64, ()> {
+        if let Some(ieee) = self.data.lock().unwrap().coordinator_ieee {
+            return Ok(ieee);
+        }
+        self.remove_last_response(&MessageType::NetworkStateResponse);
+        self.send(&commands::get_network_state());
+        match self.wait_for_response(&MessageType::NetworkStateResponse) {
+            Some(cmd) => {
+                if let Ok(state) = responses::NetworkStateResponse::from_command(&cmd) {
+                    return Ok(state.ieee_address);
+                }
+                Err(())
+            }
+            None => Err(()),
+        }
+    }
+
+    pub fn bind(
+        &mut self,
+        src_ieee: u64,
+        src_endpoint: u8,
+        cluster_id: u16,
+        dst: Destination,
+    ) -> Result<(), ()> {
+        self.remove_last_response(&MessageType::BindResponse);
+        self.send(&commands::bind_request(
+            src_ieee,
+            src_endpoint,
+            cluster_id,
+            &dst,
+        ));
+        let status = match self.wait_for_response(&MessageType::BindResponse) {
+            Some(cmd) => match responses::BindResponse::from_command(&cmd) {
+                Ok(resp) => resp.status,
+                Err(_) => return Err(()),
+            },
+            None => return Err(()),
+        };
+        if status != 0 {
+            error!("Bind of {:X} failed with status {:#X}", src_ieee, status);
+            return Err(());
+        }
+        let binding = Binding {
+            endpoint: src_endpoint,
+            cluster_id,
+            destination: dst,
+        };
+        let mut data = self.data.lock().unwrap();
+        if let Some(device) = data.devices.get_mut(&src_ieee) {
+            device.add_binding(binding);
+        }
+        data.save_devices();
+        Ok(())
+    }
+
+    pub fn bind_to_coordinator(
+        &mut self,
+        src_ieee: u64,
+        src_endpoint: u8,
+        cluster_id: u16,
+    ) -> Result<(), ()> {
+        let coordinator_ieee = self.get_coordinator_ieee()?;
+        let dst = Destination::Device(coordinator_ieee, 1);
+        self.bind(src_ieee, src_endpoint, cluster_id, dst)
+    }
+
+    pub fn unbind(
+        &mut self,
+        src_ieee: u64,
+        src_endpoint: u8,
+        cluster_id: u16,
+        dst: Destination,
+    ) -> Result<(), ()> {
+        self.remove_last_response(&MessageType::UnbindResponse);
+        self.send(&commands::unbind_request(
+            src_ieee,
+            src_endpoint,
+            cluster_id,
+            &dst,
+        ));
+        let status = match self.wait_for_response(&MessageType::UnbindResponse) {
+            Some(cmd) => match responses::UnbindResponse::from_command(&cmd) {
+                Ok(resp) => resp.status,
+                Err(_) => return Err(()),
+            },
+            None => return Err(()),
+        };
+        if status != 0 {
+            error!("Unbind of {:X} failed with status {:#X}", src_ieee, status);
+            return Err(());
+        }
+        let binding = Binding {
+            endpoint: src_endpoint,
+            cluster_id,
+            destination: dst,
+        };
+        let mut data = self.data.lock().unwrap();
+        if let Some(device) = data.devices.get_mut(&src_ieee) {
+            device.remove_binding(&binding);
+        }
+        data.save_devices();
+        Ok(())
+    }
+
+    /// Asks a device to leave the network, or to leave and rejoin. Devices that don't answer,
+    /// like sleeping end devices, are removed through the coordinator, their parent if they
+    /// joined it directly. The device is forgotten when its leave indication is received.
+    pub fn remove_device(&mut self, ieee: u64, rejoin: bool) -> Result<(), ()> {
+        let address = {
+            let data = self.data.lock().unwrap();
+            data.devices.get(&ieee).map(|device| device.short_address)
+        };
+        if let Some(address) = address {
+            if self.management_leave(address, ieee, rejoin).is_ok() {
+                return Ok(());
+            }
+        }
+        if rejoin {
+            return Err(());
+        }
+        // parents are not tracked, only the coordinator is known to be one
+        let parent_ieee = match self.get_coordinator_ieee() {
+            Ok(parent_ieee) => parent_ieee,
+            Err(()) => {
+                error!("Can't remove {:X}, its parent is unknown", ieee);
+                return Err(());
+            }
+        };
+        self.remove_last_response(&MessageType::RemoveDevice);
+        let cmd = commands::remove_device(parent_ieee, ieee);
+        self.send(&cmd);
+        match self.wait_for_status(&MessageType::RemoveDevice) {
+            Some(status) if status.status == 0 => Ok(()),
+            Some(status) => {
+                error!("{} failed: {}", cmd, status.to_string());
+                Err(())
+            }
+            None => Err(()),
+        }
+    }
+
+    /// Sends a management leave request and waits for its response. The response carries no
+    /// address, it is matched with the request by the sequence number of the request's status.
+    fn management_leave(&mut self, address: u16, ieee: u64, rejoin: bool) -> Result<(), ()> {
+        self.remove_last_response(&MessageType::ManagementLeaveRequest);
+        self.remove_last_response(&MessageType::ManagementLeaveResponse);
+        self.send(&commands::management_leave_request(address, ieee, rejoin));
+        let seq_num = match self.wait_for_status(&MessageType::ManagementLeaveRequest) {
+            Some(status) if status.status == 0 => status.seq_num,
+            _ => {
+                warn!("Leave request to {:X} was not sent", ieee);
+                return Err(());
+            }
+        };
+        for _ in 0..50 {
+            let cmd = {
+                let mut data = self.data.lock().unwrap();
+                data.last_resp.remove(&MessageType::ManagementLeaveResponse)
+            };
+            if let Some(cmd) = cmd {
+                match responses::ManagementLeaveResponse::from_command(&cmd) {
+                    // the response to another request
+                    Ok(resp) if resp.seq_num != seq_num => {}
+                    Ok(resp) if resp.status == 0 => return Ok(()),
+                    Ok(resp) => {
+                        warn!(
+                            "Leave request to {:X} failed with status {:#X}",
+                            ieee, resp.status
+                        );
+                        return Err(());
+                    }
+                    Err(_) => {}
+                }
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        warn!("No response to the leave request to {:X}", ieee);
+        Err(())
+    }
+
+    fn discover(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        extended: bool,
+    ) -> Result<Vec<DiscoveredAttribute>, ()> {
+        let mut attrs: Vec<DiscoveredAttribute> = Vec::new();
+        let mut start_attr = 0;
+        // late frames of a page are picked up with the next one, only older frames are dropped
+        self.data.lock().unwrap().discovered.clear();
+        loop {
+            let discovery = AttributeDiscovery {
+                cluster_id,
+                start_attr,
+                direction: 0,
+                manuf_id: 0,
+                max_attrs: DISCOVERY_PAGE_SIZE,
+            };
+            let cmd = match extended {
+                false => commands::attribute_discovery_request(address, 1, endpoint, &discovery),
+                true => {
+                    commands::extended_attribute_discovery_request(address, 1, endpoint, &discovery)
+                }
+            };
+            self.send(&cmd);
+            let mut received = 0;
+            let mut complete = false;
+            for _ in 0..50 {
+                {
+                    let mut data = self.data.lock().unwrap();
+                    for attr in data.discovered.drain(..) {
+                        if attr.address != address
+                            || attr.endpoint != endpoint
+                            || attr.cluster_id != cluster_id
+                        {
+                            continue;
+                        }
+                        complete |= attr.complete;
+                        if attrs.iter().all(|known| known.attr_id != attr.attr_id) {
+                            if attr.attr_id >= start_attr {
+                                received += 1;
+                            }
+                            attrs.push(attr);
+                        }
+                    }
+                }
+                if complete || received >= DISCOVERY_PAGE_SIZE as usize {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+            if complete {
+                attrs.sort_by_key(|attr| attr.attr_id);
+                return Ok(attrs);
+            }
+            // a page that timed out without the end of the list leaves it truncated
+            start_attr = match attrs.iter().map(|attr| attr.attr_id).max() {
+                Some(last_attr) if last_attr >= start_attr && last_attr != 0xffff => last_attr + 1,
+                _ => {
+                    error!(
+                        "Discovering attributes of cluster {:#X} on {:X} timed out",
+                        cluster_id, address
+                    );
+                    return Err(());
+                }
+            };
+        }
+    }
+
+    pub fn discover_attributes(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+    ) -> Result<Vec<(u16, u8)>, ()> {
+        let attrs = self.discover(address, endpoint, cluster_id, false)?;
+        Ok(attrs
+            .iter()
+            .map(|attr| (attr.attr_id, attr.data_type))
+            .collect())
+    }
+
+    pub fn discover_attributes_extended(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+    ) -> Result<Vec<(u16, u8, u8)>, ()> {
+        let attrs = self.discover(address, endpoint, cluster_id, true)?;
+        Ok(attrs
+            .iter()
+            .map(|attr| {
+                let access_control = attr.access_control.unwrap_or(0);
+                (attr.attr_id, attr.data_type, access_control)
+            })
+            .collect())
+    }
+
+    /// Returns the known devices, keyed by IEEE address.
+    pub fn get_devices(&mut self) -> HashMap<u64, Device> {
         self.send_and_wait(&commands::get_devices_list(), &MessageType::DevicesList);
-        self.wait_for_responses();
+        self.wait_for_interviews();
         let data = self.data.lock().unwrap();
         data.devices.clone()
     }
 
+    /// Restarts the interview of a device from the active endpoints request.
+    pub fn reinterview(&mut self, address: u16) -> Result<(), ()> {
+        let mut data = self.data.lock().unwrap();
+        if data.device(address).is_none() {
+            return Err(());
+        }
+        restart_interview(&mut self.sender, &mut data, address);
+        Ok(())
+    }
+
+    pub fn read_attribute(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_id: u16,
+    ) -> Result<ZclValue, ()> {
+        self.read_cluster_attribute(address, endpoint, cluster_id, attr_id, |cluster| {
+            cluster.get_value(attr_id).cloned()
+        })
+    }
+
+    fn read_cluster_attribute<T>(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_id: u16,
+        get: impl Fn(&Cluster) -> Option<T>,
+    ) -> Result<T, ()> {
+        self.read_cluster_attributes(address, endpoint, cluster_id, vec![attr_id], get)
+    }
+
+    /// Reads attributes of a cluster and waits until all of them are updated. A timeout is an
+    /// error, the cached values may be stale.
+    fn read_cluster_attributes<T>(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_list: Vec<u16>,
+        get: impl Fn(&Cluster) -> Option<T>,
+    ) -> Result<T, ()> {
+        let sent = SystemTime::now();
+        let cmd = commands::simple_read_attribute_request_vec(
+            address,
+            endpoint,
+            cluster_id,
+            attr_list.clone(),
+        );
+        self.send(&cmd);
+        let mut answered = false;
+        for _ in 0..50 {
+            {
+                let data = self.data.lock().unwrap();
+                let updated = |attr_id: &u16| {
+                    data.device(address)
+                        .and_then(|device| device.get_attribute(endpoint, cluster_id, *attr_id))
+                        .and_then(|attr| attr.updated)
+                        .map_or(false, |updated| updated >= sent)
+                };
+                if attr_list.iter().all(updated) {
+                    answered = true;
+                    break;
+                }
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        if !answered {
+            error!(
+                "Reading attributes {:X?} of cluster {:#X} from {:X} timed out",
+                attr_list, cluster_id, address
+            );
+            return Err(());
+        }
+        let data = self.data.lock().unwrap();
+        if let Some(device) = data.device(address) {
+            if let Some(endpoint) = device.get_endpoint(endpoint) {
+                if let Some(cluster) = endpoint.get_in_cluster(cluster_id) {
+                    if let Some(value) = get(cluster) {
+                        return Ok(value);
+                    }
+                }
+            }
+        }
+        Err(())
+    }
+
+    /// Reads all the Basic cluster attributes at once. Attributes the device does not support
+    /// are left to their default value.
+    pub fn read_basic_info(&mut self, address: u16, endpoint: u8) -> Result<C0000, ()> {
+        let attr_list = vec![
+            0x0000, 0x0001, 0x0002, 0x0003, 0x0004, 0x0005, 0x0006, 0x0007, 0x0010, 0x0011, 0x0012,
+            0x0013, 0x0014, 0x4000,
+        ];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0000,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::Basic(cluster) => Some(cluster.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    pub fn get_illuminance(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0400, 0, |cluster| match cluster {
+            Cluster::MeasurementIlluminance(cluster) => cluster.measured_value,
+            _ => None,
+        })
+    }
+
+    pub fn get_temperature(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0402, 0, |cluster| match cluster {
+            Cluster::MeasurementTemperature(cluster) => cluster.measured_value,
+            _ => None,
+        })
+    }
+
+    pub fn get_pressure(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0403, 0, |cluster| match cluster {
+            Cluster::MeasurementPressure(cluster) => cluster.measured_value,
+            _ => None,
+        })
+    }
+
+    pub fn get_humidity(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0405, 0, |cluster| match cluster {
+            Cluster::MeasurementHumidity(cluster) => cluster.measured_value,
+            _ => None,
+        })
+    }
+
+    pub fn get_occupancy(&mut self, address: u16, endpoint: u8) -> Result<bool, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0406, 0, |cluster| match cluster {
+            Cluster::MeasurementOccupancy(cluster) => cluster.occupied,
+            _ => None,
+        })
+    }
+
+    pub fn write_attribute(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cluster_id: u16,
+        attr_id: u16,
+        value: ZclValue,
+    ) -> Result<(), ()> {
+        let cmd =
+            commands::simple_write_attribute_request(address, endpoint, cluster_id, attr_id, value);
+        self.remove_last_response(&MessageType::WriteAttributeResponse);
+        self.send(&cmd);
+        match self.wait_for_response(&MessageType::WriteAttributeResponse) {
+            Some(cmd) => match responses::WriteAttributeResponse::from_command(&cmd) {
+                Ok(resp) if resp.attr_status == 0 => Ok(()),
+                Ok(resp) => {
+                    error!(
+                        "Write of attribute {:#X} of cluster {:#X} on {:X} failed with status {:#X}",
+                        attr_id, cluster_id, address, resp.attr_status
+                    );
+                    Err(())
+                }
+                Err(_) => Err(()),
+            },
+            None => Err(()),
+        }
+    }
+
+    pub fn get_local_temperature(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0201, 0, |cluster| match cluster {
+            Cluster::HvacThermostat(cluster) => cluster.local_temperature,
+            _ => None,
+        })
+    }
+
+    pub fn get_heating_setpoint(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0201, 0x0012, |cluster| match cluster {
+            Cluster::HvacThermostat(cluster) => cluster.occupied_heating_setpoint,
+            _ => None,
+        })
+    }
+
+    /// Sets the occupied heating setpoint, in °C.
+    pub fn set_heating_setpoint(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        setpoint: f32,
+    ) -> Result<(), ()> {
+        let value = ZclValue::Int16((setpoint * 100.0).round() as i16);
+        self.write_attribute(address, endpoint, 0x0201, 0x0012, value)
+    }
+
+    /// Raises or lowers the setpoint(s) by `amount` °C.
+    pub fn setpoint_raise_lower(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: SetpointMode,
+        amount: f32,
+    ) -> Result<(), ()> {
+        let amount = (amount * 10.0).round().clamp(-128.0, 127.0) as i8;
+        let cmd = commands::thermostat_setpoint_raise_lower(address, 1, endpoint, mode, amount);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    /// Locks the door, an error is returned if the device answers with an error status in its
+    /// lock door response or if the command can't be delivered.
+    pub fn lock(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_lock_unlock_door(address, 1, endpoint, true);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn unlock(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_lock_unlock_door(address, 1, endpoint, false);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn get_lock_state(&mut self, address: u16, endpoint: u8) -> Result<LockState, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0101, 0, |cluster| match cluster {
+            Cluster::ClosuresDoorLock(cluster) => cluster.lock_state,
+            _ => None,
+        })
+    }
+
+    pub fn cover_open(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_window_covering(address, 1, endpoint, CoverCommand::Open);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn cover_close(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_window_covering(address, 1, endpoint, CoverCommand::Close);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn cover_stop(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_window_covering(address, 1, endpoint, CoverCommand::Stop);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    /// Moves the cover to a lift percentage, 0 is open and 100 is closed.
+    pub fn cover_set_position(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        percentage: u8,
+    ) -> Result<(), ()> {
+        let cover_cmd = CoverCommand::GoToLiftPercentage(percentage.min(100));
+        let cmd = commands::action_window_covering(address, 1, endpoint, cover_cmd);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn cover_set_tilt(&mut self, address: u16, endpoint: u8, percentage: u8) -> Result<(), ()> {
+        let cover_cmd = CoverCommand::GoToTiltPercentage(percentage.min(100));
+        let cmd = commands::action_window_covering(address, 1, endpoint, cover_cmd);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn get_cover_position(&mut self, address: u16, endpoint: u8) -> Result<u8, ()> {
+        self.read_cluster_attribute(address, endpoint, 0x0102, 0x0008, |cluster| match cluster {
+            Cluster::ClosuresWindowCovering(cluster) => cluster.current_position_lift_percentage,
+            _ => None,
+        })
+    }
+
+    /// Active power in W.
+    pub fn get_active_power(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        let attr_list = vec![0x0604, 0x0605, 0x050b];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0b04,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::MeasurementElectrical(cluster) => cluster.power(),
+                _ => None,
+            },
+        )
+    }
+
+    /// RMS voltage in V.
+    pub fn get_rms_voltage(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        let attr_list = vec![0x0600, 0x0601, 0x0505];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0b04,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::MeasurementElectrical(cluster) => cluster.voltage(),
+                _ => None,
+            },
+        )
+    }
+
+    /// RMS current in A.
+    pub fn get_rms_current(&mut self, address: u16, endpoint: u8) -> Result<f32, ()> {
+        let attr_list = vec![0x0602, 0x0603, 0x0508];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0b04,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::MeasurementElectrical(cluster) => cluster.current(),
+                _ => None,
+            },
+        )
+    }
+
+    /// Current summation delivered, in kWh for electricity meters.
+    pub fn get_energy(&mut self, address: u16, endpoint: u8) -> Result<f64, ()> {
+        let attr_list = vec![0x0300, 0x0301, 0x0302, 0x0303, 0x0000];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0702,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::SmartEnergyMetering(cluster) => cluster.summation(),
+                _ => None,
+            },
+        )
+    }
+
     pub fn get_onoff(&mut self, address: u16, endpoint: u8) -> Result<bool, ()> {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 6, 0);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::GeneralOnOff(cluster) = cluster {
@@ -156,16 +1158,41 @@ impl Zigate {
         Err(())
     }
 
-    pub fn onoff(&mut self, address: u16, endpoint: u8, onoff: bool) {
+    pub fn onoff(&mut self, address: u16, endpoint: u8, onoff: bool) -> Result<(), ()> {
         let cmd = commands::action_onoff(address, 1, endpoint, onoff as u8);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    /// Makes the device identify itself (usually by blinking) for `seconds`, 0 stops it.
+    pub fn identify(&mut self, address: u16, endpoint: u8, seconds: u16) -> Result<(), ()> {
+        let cmd = commands::identify_send(address, 1, endpoint, seconds);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn identify_query(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::identify_query(address, 1, endpoint);
+        // a device that is not identifying does not answer
+        self.send_and_ack(address, &cmd)
+    }
+
+    pub fn trigger_effect(&mut self, address: u16, endpoint: u8, effect: Effect) -> Result<(), ()> {
+        let cmd = match effect {
+            Effect::Identify(effect) => {
+                commands::identify_trigger_effect(address, 1, endpoint, effect)
+            }
+            Effect::Off(effect) => {
+                let (effect_id, variant) = effect.id_and_variant();
+                commands::action_onoff_effect(address, 1, endpoint, 0, effect_id, variant)
+            }
+        };
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn get_level(&mut self, address: u16, endpoint: u8) -> Result<u8, ()> {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 8, 0);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::GeneralLevelControl(cluster) = cluster {
@@ -184,10 +1211,25 @@ impl Zigate {
         on: bool,
         level: u8,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let on = on as u8;
         let cmd = commands::action_move_onoff(address, 1, endpoint, on, level, transition_time);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn level_move(&mut self, address: u16, endpoint: u8, cmd: LevelMove) -> Result<(), ()> {
+        let cmd = cmd.to_command(address, 1, endpoint);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn level_step(&mut self, address: u16, endpoint: u8, cmd: LevelStep) -> Result<(), ()> {
+        let cmd = cmd.to_command(address, 1, endpoint);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn level_stop(&mut self, address: u16, endpoint: u8, cmd: LevelStop) -> Result<(), ()> {
+        let cmd = cmd.to_command(address, 1, endpoint);
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn get_color_capabilities(
@@ -198,7 +1240,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 0x400a);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -216,7 +1258,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 0x400b);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -234,7 +1276,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 0x400c);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -252,7 +1294,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 0);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -270,7 +1312,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 1);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -290,7 +1332,7 @@ impl Zigate {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 4);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -311,10 +1353,10 @@ impl Zigate {
         hue: u8,
         direction: u8,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let cmd =
             commands::action_move_to_hue(address, 1, endpoint, hue, direction, transition_time);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn move_to_saturation(
@@ -323,10 +1365,10 @@ impl Zigate {
         endpoint: u8,
         saturation: u8,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let cmd =
             commands::action_move_to_saturation(address, 1, endpoint, saturation, transition_time);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn move_to_hue_and_saturation(
@@ -336,7 +1378,7 @@ impl Zigate {
         hue: u8,
         saturation: u8,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let cmd = commands::action_move_to_hue_and_saturation(
             address,
             1,
@@ -345,7 +1387,7 @@ impl Zigate {
             saturation,
             transition_time,
         );
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn move_to_color(
@@ -355,16 +1397,16 @@ impl Zigate {
         x: u16,
         y: u16,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let cmd = commands::action_move_to_color(address, 1, endpoint, x, y, transition_time);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
     }
 
     pub fn get_color_temp(&mut self, address: u16, endpoint: u8) -> Result<u16, ()> {
         let cmd = commands::simple_read_attribute_request(address, endpoint, 0x0300, 7);
         self.send_and_wait(&cmd, &MessageType::ReportIndividualAttributResponse);
         let data = self.data.lock().unwrap();
-        if let Some(device) = data.devices.get(&address) {
+        if let Some(device) = data.device(address) {
             if let Some(endpoint) = device.get_endpoint(endpoint) {
                 for cluster in endpoint.get_in_clusters() {
                     if let Cluster::LightingColorControl(cluster) = cluster {
@@ -384,16 +1426,438 @@ impl Zigate {
         endpoint: u8,
         color_temp: u16,
         transition_time: u16,
-    ) {
+    ) -> Result<(), ()> {
         let cmd =
             commands::action_move_color_temp(address, 1, endpoint, color_temp, transition_time);
-        self.send(&cmd);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    /// Returns the color control cluster with the attributes needed to choose a color command,
+    /// they are read once and then cached.
+    fn get_color_control(&mut self, address: u16, endpoint: u8) -> Result<C0300, ()> {
+        {
+            let data = self.data.lock().unwrap();
+            let cached = data
+                .device(address)
+                .and_then(|device| device.get_endpoint(endpoint))
+                .and_then(|endpoint| endpoint.get_in_cluster(0x0300));
+            if let Some(Cluster::LightingColorControl(cluster)) = cached {
+                if cluster.color_capabilities.is_some() {
+                    return Ok(cluster.clone());
+                }
+            }
+        }
+        let attr_list = vec![
+            0x0008, 0x400a, 0x400b, 0x400c, 0x0011, 0x0012, 0x0015, 0x0016, 0x0019, 0x001a,
+        ];
+        self.read_cluster_attributes(
+            address,
+            endpoint,
+            0x0300,
+            attr_list,
+            |cluster| match cluster {
+                Cluster::LightingColorControl(cluster) => Some(cluster.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    /// Sends a color as xy if the device supports it, as hue and saturation otherwise.
+    fn set_color_xy(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        color: &C0300,
+        xy: Xy,
+        transition_time: u16,
+    ) -> Result<(), ()> {
+        // devices older than ZCL 6 may not report their capabilities, use the current mode
+        let use_xy = match (color.color_capabilities, &color.color_mode) {
+            (Some(caps), _) if caps.xy => true,
+            (Some(caps), _) if caps.hue_sat => false,
+            (Some(_), _) => {
+                error!(
+                    "{:X} endpoint {} supports neither xy nor hue/saturation",
+                    address, endpoint
+                );
+                return Err(());
+            }
+            (None, Some(ColorMode::HueSat)) => false,
+            (None, _) => true,
+        };
+        let xy = match color.gamut() {
+            Some(gamut) => gamut.clamp(xy),
+            None => xy,
+        };
+        if use_xy {
+            let (x, y) = xy.to_zcl();
+            self.move_to_color(address, endpoint, x, y, transition_time)
+        } else {
+            let (hue, saturation) = Rgb::from_xy(xy).to_hsv().to_zcl();
+            self.move_to_hue_and_saturation(address, endpoint, hue, saturation, transition_time)
+        }
+    }
+
+    /// Sets the color, the brightness is left to level control.
+    pub fn set_rgb(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        rgb: Rgb,
+        transition_time: u16,
+    ) -> Result<(), ()> {
+        let color = self.get_color_control(address, endpoint)?;
+        self.set_color_xy(address, endpoint, &color, rgb.to_xy(), transition_time)
+    }
+
+    /// Sets a white color temperature, emulated with xy or hue and saturation on devices that
+    /// do not support color temperature.
+    pub fn set_kelvin(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        kelvin: u16,
+        transition_time: u16,
+    ) -> Result<(), ()> {
+        let color = self.get_color_control(address, endpoint)?;
+        let use_temp = match (color.color_capabilities, &color.color_mode) {
+            (Some(caps), _) => caps.temp,
+            (None, Some(ColorMode::Temp)) => true,
+            (None, _) => false,
+        };
+        if use_temp {
+            let mireds =
+                color::kelvin_to_mireds(kelvin, color.color_temp_min, color.color_temp_max);
+            self.move_to_color_temp(address, endpoint, mireds, transition_time)
+        } else {
+            let xy = Xy::from_kelvin(kelvin);
+            self.set_color_xy(address, endpoint, &color, xy, transition_time)
+        }
+    }
+
+    pub fn move_hue(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: ColorMoveMode,
+        rate: u8,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_move_hue(address, 1, endpoint, mode, rate);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn step_hue(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: ColorStepMode,
+        step_size: u8,
+        transition_time: u8,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_step_hue(address, 1, endpoint, mode, step_size, transition_time);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn move_saturation(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: ColorMoveMode,
+        rate: u8,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_move_saturation(address, 1, endpoint, mode, rate);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn step_saturation(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: ColorStepMode,
+        step_size: u8,
+        transition_time: u8,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_step_saturation(
+            address,
+            1,
+            endpoint,
+            mode,
+            step_size,
+            transition_time,
+        );
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn enhanced_move_to_hue(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        enhanced_hue: u16,
+        direction: u8,
+        transition_time: u16,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_enhanced_move_to_hue(
+            address,
+            1,
+            endpoint,
+            enhanced_hue,
+            direction,
+            transition_time,
+        );
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn color_loop_set(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        color_loop: ColorLoopSet,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_color_loop_set(address, 1, endpoint, color_loop);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    /// Moves the color temperature continuously, a min or max of 0 means the device limit.
+    pub fn move_color_temp_rate(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        mode: ColorMoveMode,
+        rate: u16,
+        color_temp_min: u16,
+        color_temp_max: u16,
+    ) -> Result<(), ()> {
+        let cmd = commands::action_move_color_temp_rate(
+            address,
+            1,
+            endpoint,
+            mode,
+            rate,
+            color_temp_min,
+            color_temp_max,
+        );
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn step_color_temp(
+        &mut self,
+        address: u16,
+        endpoint: u8,
+        cmd: ColorTempStep,
+    ) -> Result<(), ()> {
+        let cmd = cmd.to_command(address, 1, endpoint);
+        self.send_and_confirm(address, &cmd)
+    }
+
+    pub fn stop_move_step(&mut self, address: u16, endpoint: u8) -> Result<(), ()> {
+        let cmd = commands::action_stop_move_step(address, 1, endpoint);
+        self.send_and_confirm(address, &cmd)
+    }
+}
+
+/// Writes the coordinator address as IAS CIE address and sends an enroll response, which also
+/// works as an unsolicited auto-enroll for devices that do not send an enroll request.
+fn enroll_ias_zone(sender: &mut UartSender, data: &mut ZigateData, address: u16, endpoint: u8) {
+    let next_zone_id = &mut data.next_zone_id;
+    // the device is looked up by hand to borrow the zone id counter at the same time
+    let devices = &mut data.devices;
+    let cluster = data
+        .addresses
+        .get(&address)
+        .and_then(|ieee| devices.get_mut(ieee))
+        .and_then(|device| device.get_mut_endpoint(endpoint))
+        .and_then(|endpoint| endpoint.get_mut_in_cluster(0x0500));
+    let zone_id = match cluster {
+        Some(Cluster::SecurityIasZone(cluster)) => *cluster.zone_id.get_or_insert_with(|| {
+            let zone_id = *next_zone_id;
+            *next_zone_id = (zone_id + 1) % 0xff;
+            zone_id
+        }),
+        _ => return,
+    };
+    match data.coordinator_ieee {
+        Some(ieee) => sender.send(&commands::simple_write_attribute_request(
+            address,
+            endpoint,
+            0x0500,
+            0x0010,
+            ZclValue::IeeeAddress(ieee),
+        )),
+        None => warn!("Coordinator IEEE address unknown, can't set IAS CIE address"),
+    }
+    sender.send(&commands::ias_zone_enroll_response(
+        address, 1, endpoint, 0, zone_id,
+    ));
+}
+
+/// Forgets what is known of the endpoints and interviews the device from the start.
+fn restart_interview(sender: &mut UartSender, data: &mut ZigateData, address: u16) {
+    if let Some(device) = data.device_mut(address) {
+        device.endpoints.clear();
+        device.pending_basic_attributes.clear();
+    }
+    set_interview_state(sender, data, address, InterviewState::Announced);
+}
+
+/// Moves the interview of a device to a new state and sends the request of the next step.
+fn set_interview_state(
+    sender: &mut UartSender,
+    data: &mut ZigateData,
+    address: u16,
+    state: InterviewState,
+) {
+    if let Some(device) = data.device_mut(address) {
+        device.interview_state = state;
+        device.interview_attempts = 0;
+        device.interview_deadline = None;
+        send_interview_request(sender, device);
+    }
+    match state {
+        InterviewState::Complete => info!("Interview of {:X} complete", address),
+        InterviewState::Failed => warn!("Interview of {:X} failed", address),
+        _ => debug!("Interview of {:X}: {:?}", address, state),
+    }
+    data.emit(Event::InterviewStateChanged { address, state });
+    if !state.in_progress() {
+        data.save_devices();
+    }
+}
+
+/// Sends the request of the interview step in progress and arms its timeout.
+fn send_interview_request(sender: &mut UartSender, device: &mut Device) {
+    let address = device.short_address;
+    match device.interview_state {
+        InterviewState::Announced => sender.send(&commands::active_endpoint_request(address)),
+        InterviewState::EndpointsKnown => {
+            for endpoint in device.endpoints.iter().filter(|ep| !ep.descriptor_known) {
+                sender.send(&commands::simple_descriptor_request(address, endpoint.id));
+            }
+        }
+        InterviewState::DescriptorsKnown => {
+            if let Some(endpoint) = device.find_endpoint_with_cluster(0x0000) {
+                sender.send(&commands::simple_read_attribute_request_vec(
+                    address,
+                    endpoint,
+                    0x0000,
+                    device.pending_basic_attributes.clone(),
+                ));
+            }
+        }
+        _ => return,
+    }
+    device.interview_attempts += 1;
+    device.interview_deadline = Some(SystemTime::now() + INTERVIEW_TIMEOUT);
+}
+
+/// Returns the short address of the device that sent the frame, if it carries one.
+fn frame_source(data: &ZigateData, response: &ResponseBox) -> Option<u16> {
+    match response {
+        ResponseBox::ActiveEndpointsBox(msg) => Some(msg.address),
+        ResponseBox::SimpleDescriptorResponseBox(msg) => Some(msg.address),
+        ResponseBox::ReadAttributeResponseBox(msg)
+        | ResponseBox::ReportIndividualAttributResponseBox(msg) => Some(msg.src_addr),
+        ResponseBox::WriteAttributeResponseBox(msg) => Some(msg.src_addr),
+        ResponseBox::AttributeDiscoveryResponseBox(msg) => Some(msg.src_addr),
+        ResponseBox::ExtendedAttributeDiscoveryResponseBox(msg) => Some(msg.src_addr),
+        ResponseBox::AckDataBox(msg) if msg.status == 0 => Some(msg.dst_address),
+        ResponseBox::ZoneEnrollRequestBox(msg) => {
+            data.resolve_address(msg.src_addr_mode, msg.src_address)
+        }
+        ResponseBox::ZoneStatusChangeNotificationBox(msg) => {
+            data.resolve_address(msg.src_addr_mode, msg.src_address)
+        }
+        ResponseBox::DataIndicationBox(msg) => {
+            data.resolve_address(msg.src_addr_mode, msg.src_address)
+        }
+        _ => None,
+    }
+}
+
+/// Marks the devices that have been silent for too long as unavailable. Silent mains devices
+/// are pinged first if enabled, battery devices sleep and can't be.
+fn check_availability(sender: &mut UartSender, data: &mut ZigateData) {
+    let config = match data.availability {
+        Some(config) => config,
+        None => return,
+    };
+    let now = SystemTime::now();
+    let elapsed = |time: SystemTime| now.duration_since(time).unwrap_or_default();
+    let mut unavailable = Vec::new();
+    for device in data.devices.values_mut() {
+        let silence = match device.last_seen {
+            Some(last_seen) if device.available => elapsed(last_seen),
+            _ => continue,
+        };
+        if device.is_mains() {
+            if silence < config.mains_timeout {
+                continue;
+            }
+            let basic_endpoint = device.find_endpoint_with_cluster(0x0000);
+            match (device.ping_sent, basic_endpoint) {
+                (None, Some(endpoint)) if config.ping => {
+                    debug!("Pinging {:X}", device.short_address);
+                    sender.send(&commands::simple_read_attribute_request(
+                        device.short_address,
+                        endpoint,
+                        0x0000,
+                        0x0000,
+                    ));
+                    device.ping_sent = Some(now);
+                    continue;
+                }
+                (Some(ping_sent), _) if elapsed(ping_sent) < PING_TIMEOUT => continue,
+                _ => {}
+            }
+        } else if silence < config.battery_timeout {
+            continue;
+        }
+        device.available = false;
+        unavailable.push((device.short_address, device.ieee_address));
+    }
+    for (address, ieee_address) in unavailable {
+        warn!("{:X} is unavailable", address);
+        data.emit(Event::AvailabilityChanged {
+            address,
+            ieee_address,
+            available: false,
+        });
+    }
+}
+
+/// Retries the interview steps that timed out, and gives up after too many attempts.
+fn check_interviews(sender: &mut UartSender, data: &mut ZigateData) {
+    let now = SystemTime::now();
+    let mut failed = Vec::new();
+    for device in data.devices.values_mut() {
+        let timed_out = device
+            .interview_deadline
+            .map_or(false, |deadline| deadline <= now);
+        if !device.interview_state.in_progress() || !timed_out {
+            continue;
+        }
+        if device.interview_attempts >= INTERVIEW_MAX_ATTEMPTS {
+            failed.push(device.short_address);
+        } else {
+            debug!(
+                "Interview of {:X} timed out after {:?}, retrying",
+                device.short_address, device.interview_state
+            );
+            send_interview_request(sender, device);
+        }
+    }
+    for address in failed {
+        set_interview_state(sender, data, address, InterviewState::Failed);
     }
 }
 
 fn recv_fn(rx: Receiver<Command>, mut sender: UartSender, data: Arc<Mutex<ZigateData>>) {
     loop {
-        match rx.recv() {
+        {
+            let mut data = data.lock().unwrap();
+            check_interviews(&mut sender, &mut data);
+            check_availability(&mut sender, &mut data);
+        }
+        match rx.recv_timeout(Duration::from_millis(500)) {
             Ok(cmd) => {
                 let mut data = data.lock().unwrap();
                 let msg_type = MessageType::from_u16(cmd.msg_type);
@@ -401,62 +1865,335 @@ fn recv_fn(rx: Receiver<Command>, mut sender: UartSender, data: Arc<Mutex<Zigate
                 data.last_resp.insert(msg_type, data_cmd);
                 let response = ResponseBox::from_command(&cmd);
                 debug!("recv: {}", response.to_string());
+                if let Some(address) = frame_source(&data, &response) {
+                    data.mark_seen(address, cmd.lqi());
+                }
                 match response {
-                    ResponseBox::StatusBox(_) => {
-                        let msg_type = MessageType::from_u16(cmd.msg_type);
+                    ResponseBox::StatusBox(msg) => {
+                        let msg_type = MessageType::from_u16(msg.packet_type);
                         data.last_status.insert(msg_type, cmd.clone());
+                        if let Some(mut pending) = data.awaiting_status.remove(&msg.packet_type) {
+                            if msg.status == 0 {
+                                pending.zcl_seq_num.get_or_insert(msg.seq_num);
+                                data.pending.insert(msg.seq_num, pending);
+                            }
+                        }
+                    }
+                    ResponseBox::AckDataBox(msg) => {
+                        if let Some(seq_num) = msg.seq_num {
+                            if let Some(pending) = data.pending.get_mut(&seq_num) {
+                                if pending.address == msg.dst_address {
+                                    if msg.status != 0 {
+                                        pending.result = Some(Err(msg.status));
+                                    } else {
+                                        pending.acked = true;
+                                        if !pending.expects_response {
+                                            pending.result.get_or_insert(Ok(()));
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    ResponseBox::DefaultResponseBox(msg) => {
+                        let mut address = None;
+                        let pending = data.pending.values_mut().find(|pending| {
+                            pending.answered_by(msg.seq_num, msg.endpoint, msg.cluster_id)
+                        });
+                        if let Some(pending) = pending {
+                            address = Some(pending.address);
+                            pending.result = match msg.status {
+                                0 => Some(Ok(())),
+                                status => Some(Err(status)),
+                            };
+                        }
+                        if msg.status != 0 {
+                            data.emit(Event::RequestFailed {
+                                address,
+                                seq_num: msg.seq_num,
+                                status: msg.status,
+                            });
+                        }
+                    }
+                    ResponseBox::ApsDataConfirmFailBox(msg) => {
+                        let address = data.resolve_address(msg.dst_addr_mode, msg.dst_address);
+                        if let Some(pending) = data.pending.get_mut(&msg.seq_num) {
+                            if Some(pending.address) == address {
+                                pending.result = Some(Err(msg.status));
+                            }
+                        }
+                        data.emit(Event::RequestFailed {
+                            address,
+                            seq_num: msg.seq_num,
+                            status: msg.status,
+                        });
                     }
                     ResponseBox::DeviceAnnounceBox(msg) => {
-                        if !data.devices.contains_key(&msg.short_address) {
+                        // a known device that rejoined keeps its interview data
+                        if !data.update_address(msg.ieee_address, msg.short_address) {
                             let device = Device::from_device_announce(&msg);
-                            data.devices.insert(device.short_address, device);
-                            sender.send(&commands::active_endpoint_request(msg.short_address));
-                            data.exp_resp += 1;
+                            data.add_device(device);
+                            let state = InterviewState::Announced;
+                            set_interview_state(&mut sender, &mut data, msg.short_address, state);
                         }
+                        // only now the device is known under its new address
+                        data.mark_seen(msg.short_address, cmd.lqi());
                     }
                     ResponseBox::DevicesListBox(msg) => {
                         for device in msg.devices {
-                            if !data.devices.contains_key(&device.short_address) {
+                            let address = device.short_address;
+                            if !data.update_address(device.ieee_address, address) {
                                 let device = Device::from_devices_list_elem(device);
-                                sender
-                                    .send(&commands::active_endpoint_request(device.short_address));
-                                data.exp_resp += 1;
-                                data.devices.insert(device.short_address, device);
+                                data.add_device(device);
+                                let state = InterviewState::Announced;
+                                set_interview_state(&mut sender, &mut data, address, state);
                             }
                         }
                     }
+                    ResponseBox::LeaveIndicationBox(msg) => {
+                        // a device leaving to rejoin keeps its data and will announce itself
+                        if !msg.rejoin && data.remove_device(msg.ieee_address).is_some() {
+                            info!("{:X} left the network", msg.ieee_address);
+                        }
+                        data.emit(Event::DeviceLeft {
+                            ieee_address: msg.ieee_address,
+                            rejoin: msg.rejoin,
+                        });
+                    }
                     ResponseBox::ActiveEndpointsBox(msg) => {
-                        if let Some(device) = data.devices.get_mut(&msg.address) {
+                        let mut endpoints_known = false;
+                        if let Some(device) = data.device_mut(msg.address) {
                             device.add_endpoints(&msg.endpoint_list);
-                            for endpoint in msg.endpoint_list {
-                                sender.send(&commands::simple_descriptor_request(
-                                    msg.address,
-                                    endpoint,
-                                ));
-                                data.exp_resp += 1;
-                            }
-                            data.exp_resp -= 1;
+                            endpoints_known = device.interview_state == InterviewState::Announced
+                                && !msg.endpoint_list.is_empty();
+                        }
+                        if endpoints_known {
+                            let state = InterviewState::EndpointsKnown;
+                            set_interview_state(&mut sender, &mut data, msg.address, state);
                         }
                     }
                     ResponseBox::SimpleDescriptorResponseBox(msg) => {
-                        if let Some(device) = data.devices.get_mut(&msg.address) {
+                        let mut descriptors_known = false;
+                        let mut has_basic = false;
+                        if let Some(device) = data.device_mut(msg.address) {
                             device.set_endpoints_clusters(&msg);
-                            data.exp_resp -= 1;
+                            descriptors_known = device.interview_state
+                                == InterviewState::EndpointsKnown
+                                && device.descriptors_known();
+                            has_basic = device.find_endpoint_with_cluster(0x0000).is_some();
+                            if descriptors_known && has_basic {
+                                device.pending_basic_attributes =
+                                    INTERVIEW_BASIC_ATTRIBUTES.to_vec();
+                            }
+                        }
+                        if descriptors_known {
+                            let state = InterviewState::DescriptorsKnown;
+                            set_interview_state(&mut sender, &mut data, msg.address, state);
+                            if !has_basic {
+                                let state = InterviewState::Complete;
+                                set_interview_state(&mut sender, &mut data, msg.address, state);
+                            }
+                        }
+                        if msg.in_cluster_list.contains(&0x0500)
+                            && data.device(msg.address).is_some()
+                        {
+                            enroll_ias_zone(&mut sender, &mut data, msg.address, msg.endpoint);
                         }
                     }
-                    ResponseBox::ReadAttributeResponseBox(msg) => {
-                        if let Some(device) = data.devices.get_mut(&msg.src_addr) {
+                    ResponseBox::ReadAttributeResponseBox(msg)
+                    | ResponseBox::ReportIndividualAttributResponseBox(msg) => {
+                        let threshold = data.low_battery_threshold;
+                        let mut low_battery = None;
+                        let mut basic_read = false;
+                        if let Some(device) = data.device_mut(msg.src_addr) {
+                            let previous_level = device.battery_level();
                             device.update_cluster(&msg);
+                            // unsupported attributes come with an error status, they count too
+                            let pending = &mut device.pending_basic_attributes;
+                            if msg.cluster_id == 0x0000 && pending.contains(&msg.attr_enum) {
+                                pending.retain(|attr_id| *attr_id != msg.attr_enum);
+                                basic_read = pending.is_empty()
+                                    && device.interview_state == InterviewState::DescriptorsKnown;
+                            }
+                            if let Some(level) = device.battery_level() {
+                                if level < threshold
+                                    && previous_level.map_or(true, |prev| prev >= threshold)
+                                {
+                                    low_battery = Some(level);
+                                }
+                            }
+                        }
+                        if basic_read {
+                            let address = msg.src_addr;
+                            set_interview_state(
+                                &mut sender,
+                                &mut data,
+                                address,
+                                InterviewState::BasicRead,
+                            );
+                            set_interview_state(
+                                &mut sender,
+                                &mut data,
+                                address,
+                                InterviewState::Complete,
+                            );
+                        }
+                        if let Some(level) = low_battery {
+                            warn!("Low battery on {:X}: {}%", msg.src_addr, level);
+                            data.emit(Event::LowBattery {
+                                address: msg.src_addr,
+                                level,
+                            });
+                        }
+                        if let Ok(value) = msg.value() {
+                            data.emit(Event::AttributeReport {
+                                address: msg.src_addr,
+                                endpoint: msg.endpoint,
+                                cluster_id: msg.cluster_id,
+                                attr_id: msg.attr_enum,
+                                value,
+                            });
                         }
                     }
-                    ResponseBox::ReportIndividualAttributResponseBox(msg) => {
-                        if let Some(device) = data.devices.get_mut(&msg.src_addr) {
-                            device.update_cluster(&msg);
+                    ResponseBox::NetworkStateResponseBox(msg) => {
+                        data.coordinator_ieee = Some(msg.ieee_address);
+                    }
+                    ResponseBox::ZoneEnrollRequestBox(msg) => {
+                        match data.resolve_address(msg.src_addr_mode, msg.src_address) {
+                            Some(address) => {
+                                enroll_ias_zone(&mut sender, &mut data, address, msg.endpoint)
+                            }
+                            None => warn!("Zone enroll request from unknown device"),
+                        }
+                    }
+                    ResponseBox::ZoneStatusChangeNotificationBox(msg) => {
+                        let address = match data.resolve_address(msg.src_addr_mode, msg.src_address)
+                        {
+                            Some(address) => address,
+                            None => continue,
+                        };
+                        let status = ZoneStatus::from_bits(msg.zone_status);
+                        if let Some(device) = data.device_mut(address) {
+                            if let Some(endpoint) = device.get_mut_endpoint(msg.endpoint) {
+                                if let Some(Cluster::SecurityIasZone(cluster)) =
+                                    endpoint.get_mut_in_cluster(0x0500)
+                                {
+                                    cluster.zone_status = Some(status);
+                                    cluster.zone_id = Some(msg.zone_id);
+                                }
+                            }
+                        }
+                        data.emit(Event::ZoneStatusChange {
+                            address,
+                            endpoint: msg.endpoint,
+                            zone_id: msg.zone_id,
+                            status,
+                        });
+                    }
+                    ResponseBox::DataIndicationBox(msg) => {
+                        let address = match data.resolve_address(msg.src_addr_mode, msg.src_address)
+                        {
+                            Some(address) => address,
+                            None => continue,
+                        };
+                        let (header, payload) = match msg.zcl_frame() {
+                            Ok(frame) => frame,
+                            Err(err) => {
+                                warn!("Invalid ZCL frame from {:X}: {}", address, err);
+                                continue;
+                            }
+                        };
+                        let server_to_client = header.frame_control.server_to_client;
+                        match (msg.cluster_id, header.command_id) {
+                            // Lock Door and Unlock Door responses
+                            (0x0101, 0x00) | (0x0101, 0x01)
+                                if header.frame_control.cluster_specific && server_to_client =>
+                            {
+                                let status = match payload.first() {
+                                    Some(status) => *status,
+                                    None => {
+                                        warn!("Invalid lock door response from {:X}", address);
+                                        continue;
+                                    }
+                                };
+                                let pending = data.pending.values_mut().find(|pending| {
+                                    pending.address == address
+                                        && pending.answered_by(
+                                            header.seq_num,
+                                            msg.src_endpoint,
+                                            msg.cluster_id,
+                                        )
+                                });
+                                if let Some(pending) = pending {
+                                    pending.result = match status {
+                                        0 => Some(Ok(())),
+                                        status => Some(Err(status)),
+                                    };
+                                }
+                            }
+                            // Door Lock Operation Event Notification
+                            (0x0101, 0x20) if header.frame_control.cluster_specific => {
+                                match OperationEvent::from_zcl_payload(payload) {
+                                    Ok(event) => data.emit(Event::DoorLockOperation {
+                                        address,
+                                        endpoint: msg.src_endpoint,
+                                        event,
+                                    }),
+                                    Err(err) => {
+                                        warn!("Invalid operation event from {:X}: {}", address, err)
+                                    }
+                                }
+                            }
+                            _ => debug!(
+                                "Unhandled ZCL command {:#X} on cluster {:#X} from {:X}",
+                                header.command_id, msg.cluster_id, address
+                            ),
+                        }
+                    }
+                    ResponseBox::AttributeDiscoveryResponseBox(msg) => {
+                        if let Some(device) = data.device_mut(msg.src_addr) {
+                            device.set_attribute_info(
+                                msg.endpoint,
+                                msg.cluster_id,
+                                msg.attr_id,
+                                msg.data_type,
+                                None,
+                            );
+                        }
+                        data.discovered.push(DiscoveredAttribute {
+                            address: msg.src_addr,
+                            endpoint: msg.endpoint,
+                            cluster_id: msg.cluster_id,
+                            attr_id: msg.attr_id,
+                            data_type: msg.data_type,
+                            access_control: None,
+                            complete: msg.complete,
+                        });
+                    }
+                    ResponseBox::ExtendedAttributeDiscoveryResponseBox(msg) => {
+                        if let Some(device) = data.device_mut(msg.src_addr) {
+                            device.set_attribute_info(
+                                msg.endpoint,
+                                msg.cluster_id,
+                                msg.attr_id,
+                                msg.data_type,
+                                Some(msg.access_control),
+                            );
                         }
+                        data.discovered.push(DiscoveredAttribute {
+                            address: msg.src_addr,
+                            endpoint: msg.endpoint,
+                            cluster_id: msg.cluster_id,
+                            attr_id: msg.attr_id,
+                            data_type: msg.data_type,
+                            access_control: Some(msg.access_control),
+                            complete: msg.complete,
+                        });
                     }
                     _ => {}
                 }
             }
+            Err(RecvTimeoutError::Timeout) => {}
             Err(err) => error!("error: {}", err),
         }
     }